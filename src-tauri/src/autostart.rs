@@ -0,0 +1,217 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::error::{AppError, AppResult};
+use crate::system_settings::{SystemSettingsPatch, SystemSettingsStore};
+
+/// Passed by the OS autostart entry, see the plugin setup in `lib.rs`
+pub const AUTOSTART_ARG: &str = "--autostarted";
+
+/// What the OS autostart entry actually looks like, for troubleshooting
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartDiagnostics {
+    /// What the autostart plugin reports
+    pub enabled: bool,
+    /// Where the entry lives: a file path, or a registry key on Windows
+    pub entry_location: Option<String>,
+    pub entry_exists: bool,
+    /// The command the entry launches
+    pub entry_command: Option<String>,
+    pub current_binary: Option<String>,
+    pub points_at_current_binary: bool,
+    pub error: Option<String>,
+}
+
+/// Check if app was autostarted
+#[tauri::command]
+pub fn was_autostarted() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// The OS entry can be removed behind our back, so treat it as the source of truth at startup
+pub fn sync_launch_on_startup(app: &AppHandle) {
+    let (Some(store), Ok(enabled)) = (app.try_state::<SystemSettingsStore>(), app.autolaunch().is_enabled()) else {
+        return;
+    };
+    let patch = SystemSettingsPatch {
+        launch_on_startup: Some(enabled),
+        ..Default::default()
+    };
+    if let Err(e) = store.update(app, patch) {
+        log::warn!("Failed to record autostart state: {}", e);
+    }
+}
+
+/// Show the main window unless this is an autostart and the user wants to start minimized.
+/// The window is created hidden, so it never flashes before this decides.
+pub fn apply_startup_visibility(app: &AppHandle) {
+    let start_hidden = was_autostarted() && crate::system_settings::current(app).start_minimized;
+    if start_hidden {
+        log::info!("Autostarted minimized, keeping the main window hidden");
+        return;
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn autostart_error(e: impl std::fmt::Display) -> AppError {
+    AppError::internal(format!("Autostart failed: {}", e))
+}
+
+fn set_enabled(app: &AppHandle, store: &SystemSettingsStore, enabled: bool) -> AppResult<bool> {
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch.enable().map_err(autostart_error)?;
+    } else {
+        autolaunch.disable().map_err(autostart_error)?;
+    }
+    let enabled = autolaunch.is_enabled().map_err(autostart_error)?;
+
+    store.update(
+        app,
+        SystemSettingsPatch {
+            launch_on_startup: Some(enabled),
+            ..Default::default()
+        },
+    )?;
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub fn enable_autostart(app: AppHandle, store: State<SystemSettingsStore>) -> AppResult<bool> {
+    set_enabled(&app, &store, true)
+}
+
+#[tauri::command]
+pub fn disable_autostart(app: AppHandle, store: State<SystemSettingsStore>) -> AppResult<bool> {
+    set_enabled(&app, &store, false)
+}
+
+#[tauri::command]
+pub fn is_autostart_enabled(app: AppHandle) -> AppResult<bool> {
+    app.autolaunch().is_enabled().map_err(autostart_error)
+}
+
+#[tauri::command]
+pub fn autostart_diagnostics(app: AppHandle) -> AutostartDiagnostics {
+    let enabled = app.autolaunch().is_enabled().unwrap_or(false);
+    let current_binary = launch_binary();
+    let name = app.package_info().name.clone();
+
+    let mut diagnostics = AutostartDiagnostics {
+        enabled,
+        entry_location: None,
+        entry_exists: false,
+        entry_command: None,
+        current_binary: current_binary.as_ref().map(|p| p.to_string_lossy().into_owned()),
+        points_at_current_binary: false,
+        error: None,
+    };
+
+    match read_entry(&app, &name) {
+        Ok((location, command)) => {
+            diagnostics.entry_location = Some(location);
+            diagnostics.entry_exists = command.is_some();
+            diagnostics.points_at_current_binary = match (&command, &current_binary) {
+                (Some(command), Some(binary)) => command_targets(command, binary),
+                _ => false,
+            };
+            diagnostics.entry_command = command;
+        }
+        Err(e) => diagnostics.error = Some(e),
+    }
+
+    diagnostics
+}
+
+/// The binary the plugin registers: the AppImage when running from one, otherwise this executable
+fn launch_binary() -> Option<PathBuf> {
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Some(PathBuf::from(appimage));
+    }
+    std::env::current_exe().ok()
+}
+
+fn command_targets(command: &str, binary: &Path) -> bool {
+    let binary = binary.to_string_lossy();
+    let command = command.trim().trim_start_matches('"');
+    command.starts_with(binary.as_ref())
+}
+
+/// Where the entry is expected and the command it runs, `None` when it doesn't exist
+#[cfg(target_os = "linux")]
+fn read_entry(app: &AppHandle, name: &str) -> Result<(String, Option<String>), String> {
+    let path = app
+        .path()
+        .config_dir()
+        .map_err(|e| e.to_string())?
+        .join("autostart")
+        .join(format!("{}.desktop", name));
+
+    let command = match std::fs::read_to_string(&path) {
+        Ok(content) => content
+            .lines()
+            .find_map(|line| line.strip_prefix("Exec="))
+            .map(str::to_string),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    Ok((path.to_string_lossy().into_owned(), command))
+}
+
+#[cfg(target_os = "macos")]
+fn read_entry(app: &AppHandle, name: &str) -> Result<(String, Option<String>), String> {
+    let path = app
+        .path()
+        .home_dir()
+        .map_err(|e| e.to_string())?
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", name));
+
+    let command = match std::fs::read_to_string(&path) {
+        // The first <string> in ProgramArguments is the binary
+        Ok(content) => content
+            .split("<key>ProgramArguments</key>")
+            .nth(1)
+            .and_then(|rest| rest.split("<string>").nth(1))
+            .and_then(|rest| rest.split("</string>").next())
+            .map(str::to_string),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    Ok((path.to_string_lossy().into_owned(), command))
+}
+
+#[cfg(target_os = "windows")]
+fn read_entry(_app: &AppHandle, name: &str) -> Result<(String, Option<String>), String> {
+    use std::os::windows::process::CommandExt;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    /// Keeps `reg` from flashing a console window over the settings page
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = std::process::Command::new("reg")
+        .args(["query", RUN_KEY, "/v", name])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to query the registry: {}", e))?;
+
+    // A missing value makes `reg query` fail; otherwise the line reads `<name>    REG_SZ    <command>`
+    let command = output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        .and_then(|stdout| {
+            stdout
+                .lines()
+                .find_map(|line| line.split_once("REG_SZ").map(|(_, command)| command.trim().to_string()))
+        });
+    Ok((format!(r"{}\{}", RUN_KEY, name), command))
+}
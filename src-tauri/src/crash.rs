@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult};
+use crate::logging;
+
+/// Crash reports waiting for the user, under the app log dir
+const CRASH_DIR: &str = "crashes";
+const LOG_TAIL_LINES: usize = 200;
+
+/// What the panic hook writes; `redacted` flips once the next launch has scrubbed the log tail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// RFC 3339
+    pub occurred_at: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub log_tail: String,
+    #[serde(default)]
+    pub redacted: bool,
+}
+
+impl CrashReport {
+    fn new(message: String, location: Option<String>, app_version: &str, log_file: Option<&Path>) -> Self {
+        let now = chrono::Local::now();
+        Self {
+            id: format!("crash-{}", now.format("%Y%m%d-%H%M%S-%3f")),
+            app_version: app_version.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            occurred_at: now.to_rfc3339(),
+            thread: std::thread::current().name().unwrap_or("<unnamed>").to_string(),
+            message,
+            location,
+            backtrace: Backtrace::force_capture().to_string(),
+            log_tail: log_file
+                .and_then(|path| logging::tail_file(path, LOG_TAIL_LINES).ok())
+                .unwrap_or_default(),
+            redacted: false,
+        }
+    }
+}
+
+fn crash_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(app.path().app_log_dir()?.join(CRASH_DIR))
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> AppResult<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", report.id));
+    let content = serde_json::to_string_pretty(report)?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, &path)?;
+    Ok(path)
+}
+
+thread_local! {
+    /// Set while this thread runs the hook; a second panic meanwhile goes straight to the default one
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Write a crash report for every panic, then run the default hook. Everything the hook needs is
+/// captured up front: app state may be locked or poisoned by the time it runs.
+pub fn install_hook(app: &AppHandle) {
+    let dir = match crash_dir(app) {
+        Ok(dir) => dir,
+        Err(e) => {
+            log::warn!("Crash reports are disabled: {}", e);
+            return;
+        }
+    };
+    let log_file = logging::log_file(app).ok();
+    let app_version = app.package_info().version.to_string();
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if IN_HOOK.replace(true) {
+            previous(info);
+            return;
+        }
+
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let location = info
+            .location()
+            .map(|location| format!("{}:{}:{}", location.file(), location.line(), location.column()));
+
+        // Written before anything is logged: the panic may have come from inside the logger,
+        // which would then deadlock or panic again
+        let report = CrashReport::new(message, location, &app_version, log_file.as_deref());
+        let written = write_report(&dir, &report);
+        previous(info);
+
+        log::error!("Panicked: {}", info);
+        match written {
+            Ok(path) => log::error!("Crash report written to {}", path.display()),
+            Err(e) => log::error!("Failed to write crash report: {}", e),
+        }
+        IN_HOOK.set(false);
+    }));
+}
+
+/// Reports not dismissed yet, newest first
+fn pending_reports(app: &AppHandle) -> Vec<(PathBuf, CrashReport)> {
+    let Ok(entries) = crash_dir(app).and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let report = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<CrashReport>(&content).ok())?;
+            Some((path, report))
+        })
+        .collect();
+    reports.sort_by(|(_, a), (_, b)| b.occurred_at.cmp(&a.occurred_at));
+    reports
+}
+
+/// Scrub reports left by the last run, once the library is there to tell titles apart.
+/// The hook itself can't: the library lock may be what it panicked under.
+pub fn redact_pending(app: &AppHandle) {
+    let Ok(dir) = crash_dir(app) else {
+        return;
+    };
+    for (_, mut report) in pending_reports(app).into_iter().filter(|(_, report)| !report.redacted) {
+        report.log_tail = crate::diagnostics::redact_log(app, &report.log_tail);
+        report.backtrace = crate::diagnostics::redact_log(app, &report.backtrace);
+        report.redacted = true;
+        if let Err(e) = write_report(&dir, &report) {
+            log::warn!("Failed to redact crash report {}: {}", report.id, e);
+        }
+    }
+}
+
+/// Called by the main window once it has loaded, to offer reporting what crashed last time
+#[tauri::command]
+pub fn get_crash_reports(app: AppHandle) -> Vec<CrashReport> {
+    pending_reports(&app).into_iter().map(|(_, report)| report).collect()
+}
+
+/// Delete a report once the user has looked at it
+#[tauri::command]
+pub fn dismiss_crash_report(app: AppHandle, id: String) -> AppResult<()> {
+    let (path, _) = pending_reports(&app)
+        .into_iter()
+        .find(|(_, report)| report.id == id)
+        .ok_or_else(|| AppError::not_found(format!("No crash report {}", id)))?;
+    Ok(std::fs::remove_file(path)?)
+}
@@ -0,0 +1,393 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::import::ImportFormat;
+use crate::library::{LibraryStore, MediaRef};
+use crate::media::{self, MediaType, WatchStatus};
+
+pub const SCHEME: &str = "watchfolio";
+
+/// A parsed `watchfolio://` link
+#[derive(Debug, Clone)]
+pub enum DeepLink {
+    /// `watchfolio://media/movie/603`
+    Media { media_type: MediaType, tmdb_id: u64 },
+    /// `watchfolio://add?tmdb=603&type=movie&status=willWatch[&title=The%20Matrix]`
+    Add {
+        media: MediaRef,
+        status: WatchStatus,
+    },
+    /// `watchfolio://library` or `watchfolio://library/watching`
+    Library { status: Option<WatchStatus> },
+    /// `watchfolio://import?file=/path/to/export.csv`
+    Import { file: PathBuf },
+}
+
+/// Links that arrive before the main window is listening, `None` once it says it is
+pub struct PendingLinks(Mutex<Option<Vec<Url>>>);
+
+impl Default for PendingLinks {
+    fn default() -> Self {
+        Self(Mutex::new(Some(Vec::new())))
+    }
+}
+
+/// Payload of `deep-link:add`, for the frontend to fetch details and add the title itself
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddPayload {
+    tmdb_id: u64,
+    #[serde(rename = "media_type")]
+    media_type: MediaType,
+    status: WatchStatus,
+    /// The window is hidden, so confirm with a notification instead of a toast
+    in_background: bool,
+}
+
+/// Payload of `deep-link:error`
+#[derive(Clone, Serialize)]
+struct RejectedLink {
+    url: String,
+    message: String,
+}
+
+impl DeepLink {
+    pub fn parse(url: &Url) -> Result<Self, String> {
+        if url.scheme() != SCHEME {
+            return Err(format!("Unsupported scheme \"{}\", expected {}://", url.scheme(), SCHEME));
+        }
+
+        // `watchfolio://media/movie/603` puts "media" in the host, the rest in the path
+        let mut segments: Vec<&str> = url.host_str().into_iter().collect();
+        segments.extend(url.path_segments().into_iter().flatten().filter(|s| !s.is_empty()));
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        match segments.as_slice() {
+            ["media", media_type, tmdb_id] => Ok(DeepLink::Media {
+                media_type: parse_media_type(media_type)?,
+                tmdb_id: parse_tmdb_id(tmdb_id)?,
+            }),
+            ["media", ..] => Err("Expected media/<movie|tv>/<tmdb id>".to_string()),
+            ["add"] => {
+                let tmdb_id = parse_tmdb_id(&query("tmdb").ok_or("Missing \"tmdb\" parameter")?)?;
+                let media_type = parse_media_type(&query("type").ok_or("Missing \"type\" parameter")?)?;
+                let status = match query("status") {
+                    Some(status) => parse_status(&status)?,
+                    None => WatchStatus::WillWatch,
+                };
+                if status == WatchStatus::None {
+                    return Err("Status \"none\" can't be used to add a title".to_string());
+                }
+                Ok(DeepLink::Add {
+                    media: MediaRef {
+                        tmdb_id,
+                        media_type,
+                        title: query("title").unwrap_or_default(),
+                    },
+                    status,
+                })
+            }
+            ["library"] => Ok(DeepLink::Library { status: None }),
+            ["library", status] => Ok(DeepLink::Library {
+                status: Some(parse_status(status)?),
+            }),
+            ["import"] => {
+                let file = PathBuf::from(query("file").ok_or("Missing \"file\" parameter")?);
+                if !file.is_absolute() {
+                    return Err(format!("Import path must be absolute: {}", file.display()));
+                }
+                if ImportFormat::from_path(&file).is_none() {
+                    return Err(format!("Unsupported import file: {}", file.display()));
+                }
+                Ok(DeepLink::Import { file })
+            }
+            [] => Err("Link has no route".to_string()),
+            [route, ..] => Err(format!("Unknown route \"{}\"", route)),
+        }
+    }
+}
+
+fn parse_media_type(value: &str) -> Result<MediaType, String> {
+    MediaType::parse(value).ok_or_else(|| format!("Invalid media type \"{}\", expected movie or tv", value))
+}
+
+fn parse_tmdb_id(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .ok()
+        .filter(|id| *id > 0)
+        .ok_or_else(|| format!("Invalid TMDB id \"{}\"", value))
+}
+
+/// Accepts both the `willWatch` value and the `will-watch` route slug
+fn parse_status(value: &str) -> Result<WatchStatus, String> {
+    WatchStatus::parse(value)
+        .or_else(|| {
+            [
+                WatchStatus::Watching,
+                WatchStatus::WillWatch,
+                WatchStatus::Completed,
+                WatchStatus::OnHold,
+                WatchStatus::Dropped,
+            ]
+            .into_iter()
+            .find(|status| media::slugify(status.as_str()) == value)
+        })
+        .ok_or_else(|| format!("Invalid status \"{}\"", value))
+}
+
+/// Register the scheme where the OS needs it at runtime and start handling links
+pub fn init(app: &AppHandle) {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    // Installed builds register the scheme from the bundle config; dev builds on Linux and Windows don't
+    #[cfg(all(debug_assertions, any(target_os = "linux", target_os = "windows")))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("Failed to register deep link schemes: {}", e);
+    }
+
+    app.manage(PendingLinks::default());
+
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&app_handle, &url);
+        }
+    });
+
+    // Links the app was launched with; queued until the frontend is listening
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            for url in urls {
+                handle_url(app, &url);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to read launch deep links: {}", e),
+    }
+}
+
+pub fn handle_url(app: &AppHandle, url: &Url) {
+    if let Some(pending) = app.try_state::<PendingLinks>() {
+        if let Ok(mut pending) = pending.0.lock() {
+            if let Some(queue) = pending.as_mut() {
+                log::info!("Queueing deep link {} until the window is listening", url);
+                queue.push(url.clone());
+                return;
+            }
+        }
+    }
+
+    match DeepLink::parse(url) {
+        Ok(link) => {
+            log::info!("Opening deep link {}", url);
+            dispatch(app, link);
+        }
+        Err(message) => {
+            log::warn!("Rejected deep link {}: {}", url, message);
+            reject(app, url, message);
+        }
+    }
+}
+
+fn reject(app: &AppHandle, url: &Url, message: String) {
+    match app.get_webview_window("main") {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            let _ = window.emit_to(
+                "main",
+                "deep-link:error",
+                RejectedLink {
+                    url: url.to_string(),
+                    message,
+                },
+            );
+        }
+        _ => notify(app, "Couldn't open link", &message),
+    }
+}
+
+/// Send the link to the main window, showing it unless the link can be handled from the tray
+pub fn dispatch(app: &AppHandle, link: DeepLink) {
+    let window = app.get_webview_window("main");
+    let visible = window
+        .as_ref()
+        .is_some_and(|window| window.is_visible().unwrap_or(false));
+
+    match link {
+        DeepLink::Add { media, status } => {
+            // Added from the tray while hidden, confirmed with a notification
+            if let Some(window) = window.filter(|_| visible) {
+                let _ = window.set_focus();
+            }
+            let _ = app.emit_to(
+                "main",
+                "deep-link:add",
+                AddPayload {
+                    tmdb_id: media.tmdb_id,
+                    media_type: media.media_type,
+                    status,
+                    in_background: !visible,
+                },
+            );
+        }
+        DeepLink::Media { media_type, tmdb_id } => {
+            let title = app
+                .try_state::<LibraryStore>()
+                .and_then(|store| store.find(media_type, tmdb_id).ok().flatten())
+                .map(|item| item.title)
+                .unwrap_or_default();
+            navigate(app, media::details_path(media_type, tmdb_id, &title));
+        }
+        DeepLink::Library { status } => {
+            let path = match status {
+                Some(status) => format!("/library/{}", media::slugify(status.as_str())),
+                None => "/library".to_string(),
+            };
+            navigate(app, path);
+        }
+        DeepLink::Import { file } => {
+            // The preview needs the window to be confirmed
+            show_main_window(app);
+            crate::file_drop::handle_drop(app, vec![file]);
+        }
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn navigate(app: &AppHandle, path: String) {
+    show_main_window(app);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit_to("main", "deep-link:navigate", path);
+    }
+}
+
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    use tauri_plugin_notification::NotificationExt;
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Called by the main window once its deep link listeners are in place; handles the links queued
+/// since launch. Emitting them any earlier, even from a finished page load, can beat the listeners.
+#[tauri::command]
+pub fn deep_links_ready(app: AppHandle) {
+    let queued = app
+        .try_state::<PendingLinks>()
+        .and_then(|pending| pending.0.lock().ok()?.take())
+        .unwrap_or_default();
+    for url in queued {
+        handle_url(&app, &url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<DeepLink, String> {
+        DeepLink::parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_media_routes() {
+        assert!(matches!(
+            parse("watchfolio://media/movie/603"),
+            Ok(DeepLink::Media { media_type: MediaType::Movie, tmdb_id: 603 })
+        ));
+        assert!(matches!(
+            parse("watchfolio://media/tv/1399/"),
+            Ok(DeepLink::Media { media_type: MediaType::Tv, tmdb_id: 1399 })
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_media_routes() {
+        assert!(parse("watchfolio://media/movie").is_err());
+        assert!(parse("watchfolio://media/book/603").is_err());
+        assert!(parse("watchfolio://media/movie/0").is_err());
+        assert!(parse("watchfolio://media/movie/-1").is_err());
+        assert!(parse("watchfolio://media/movie/abc").is_err());
+    }
+
+    #[test]
+    fn parses_add_with_defaults() {
+        let Ok(DeepLink::Add { media, status }) = parse("watchfolio://add?tmdb=603&type=movie") else {
+            panic!("expected an add link");
+        };
+        assert_eq!(media.tmdb_id, 603);
+        assert_eq!(media.media_type, MediaType::Movie);
+        assert_eq!(media.title, "");
+        assert_eq!(status, WatchStatus::WillWatch);
+    }
+
+    #[test]
+    fn parses_add_with_status_and_title() {
+        let Ok(DeepLink::Add { media, status }) =
+            parse("watchfolio://add?tmdb=1399&type=tv&status=watching&title=Game%20of%20Thrones")
+        else {
+            panic!("expected an add link");
+        };
+        assert_eq!(media.title, "Game of Thrones");
+        assert_eq!(status, WatchStatus::Watching);
+    }
+
+    #[test]
+    fn rejects_bad_add_links() {
+        assert!(parse("watchfolio://add?type=movie").is_err());
+        assert!(parse("watchfolio://add?tmdb=603").is_err());
+        assert!(parse("watchfolio://add?tmdb=603&type=movie&status=none").is_err());
+        assert!(parse("watchfolio://add?tmdb=603&type=movie&status=later").is_err());
+    }
+
+    #[test]
+    fn parses_library_routes_with_values_and_slugs() {
+        assert!(matches!(parse("watchfolio://library"), Ok(DeepLink::Library { status: None })));
+        assert!(matches!(
+            parse("watchfolio://library/onHold"),
+            Ok(DeepLink::Library { status: Some(WatchStatus::OnHold) })
+        ));
+        assert!(matches!(
+            parse("watchfolio://library/will-watch"),
+            Ok(DeepLink::Library { status: Some(WatchStatus::WillWatch) })
+        ));
+        assert!(parse("watchfolio://library/someday").is_err());
+    }
+
+    #[test]
+    fn parses_import_of_absolute_supported_files() {
+        let file = if cfg!(windows) { "C:/exports/library.csv" } else { "/exports/library.csv" };
+        let Ok(DeepLink::Import { file: parsed }) = parse(&format!("watchfolio://import?file={}", file)) else {
+            panic!("expected an import link");
+        };
+        assert_eq!(parsed, PathBuf::from(file));
+    }
+
+    #[test]
+    fn rejects_bad_import_files() {
+        assert!(parse("watchfolio://import").is_err());
+        assert!(parse("watchfolio://import?file=exports/library.csv").is_err());
+        let file = if cfg!(windows) { "C:/exports/library.txt" } else { "/exports/library.txt" };
+        assert!(parse(&format!("watchfolio://import?file={}", file)).is_err());
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_routes() {
+        assert!(parse("https://media/movie/603").is_err());
+        assert!(parse("watchfolio://").is_err());
+        assert!(parse("watchfolio://settings").is_err());
+    }
+}
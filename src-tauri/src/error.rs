@@ -0,0 +1,138 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Stable identifiers the frontend can branch on; messages are for people and may change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+    InvalidInput,
+    NotFound,
+    PermissionDenied,
+    DiskFull,
+    Io,
+    Database,
+    /// A thread panicked while holding a lock; the guarded state may be inconsistent
+    LockPoisoned,
+    WindowNotFound,
+    /// Managed state or a directory the command needs isn't there, e.g. on a platform without it
+    Unavailable,
+    InvalidShortcut,
+    /// The accelerator is taken, by another action or by another app
+    ShortcutInUse,
+    NoUpdateAvailable,
+    UpdateFailed,
+    Internal,
+}
+
+/// The error every command returns: `{ code, message, details? }`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Unavailable, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn window_not_found(label: &str) -> Self {
+        Self::new(ErrorCode::WindowNotFound, format!("Window \"{}\" is not open", label))
+            .with_details(serde_json::json!({ "label": label }))
+    }
+
+    /// Prefix the message with what was being attempted, keeping the code
+    pub fn context(mut self, context: &str) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        let code = if is_disk_full(&error) {
+            ErrorCode::DiskFull
+        } else {
+            match error.kind() {
+                std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+                std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+                _ => ErrorCode::Io,
+            }
+        };
+        Self::new(code, error.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(error: std::sync::PoisonError<T>) -> Self {
+        Self::new(ErrorCode::LockPoisoned, error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(error: rusqlite::Error) -> Self {
+        Self::new(ErrorCode::Database, error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        Self::internal(error.to_string())
+    }
+}
+
+/// `ErrorKind::StorageFull` is newer than our MSRV, so check the OS codes directly
+fn is_disk_full(error: &std::io::Error) -> bool {
+    match error.raw_os_error() {
+        #[cfg(unix)]
+        Some(28) => true, // ENOSPC
+        #[cfg(windows)]
+        Some(39) | Some(112) => true, // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+        _ => false,
+    }
+}
@@ -0,0 +1,824 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::{AppError, AppResult};
+use crate::media::LibraryMedia;
+
+/// Columns written for CSV exports, in order
+const CSV_HEADERS: [&str; 17] = [
+    "id",
+    "tmdbId",
+    "media_type",
+    "title",
+    "posterPath",
+    "releaseDate",
+    "status",
+    "isFavorite",
+    "userRating",
+    "addedAt",
+    "lastUpdatedAt",
+    "notes",
+    "overview",
+    "genres",
+    "networks",
+    "rating",
+    "totalMinutesRuntime",
+];
+
+/// How long an open export may go without a chunk before it is cancelled
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Fields that are never written to an export file
+const EXCLUDED_FIELDS: [&str; 2] = ["library", "userId"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// Target file, or a directory to write a dated `watchfolio-YYYY-MM-DD.<ext>` file into
+    pub path: String,
+    pub format: ExportFormat,
+    /// Total number of items the frontend intends to send, used for progress reporting
+    pub total: Option<usize>,
+    /// How many previous copies of the target to keep as `.1`, `.2`, ... (none by default)
+    #[serde(default)]
+    pub keep_backups: usize,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Returned once an export has been moved into place
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    pub path: String,
+    pub items_written: usize,
+    pub bytes_written: u64,
+    /// Previous exports that were rotated to make room, newest first
+    pub rotated: Vec<String>,
+}
+
+fn unknown_export(export_id: u64) -> AppError {
+    AppError::not_found(format!("Unknown export: {}", export_id))
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportProgress {
+    export_id: u64,
+    written: usize,
+    total: Option<usize>,
+}
+
+/// Serializes library items into a temp file next to the target, one at a time,
+/// and only replaces the target once everything is on disk
+pub struct ExportWriter {
+    format: ExportFormat,
+    target: PathBuf,
+    temp_path: PathBuf,
+    writer: BufWriter<File>,
+    written: usize,
+}
+
+impl ExportWriter {
+    pub fn create(path: &str, format: ExportFormat) -> std::io::Result<Self> {
+        let target = resolve_target(Path::new(path), format);
+        let temp_path = temp_path_for(&target);
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+
+        let header = match format {
+            ExportFormat::Json => writer.write_all(b"["),
+            ExportFormat::Csv => write_csv(&mut writer, [CSV_HEADERS]),
+            ExportFormat::Ndjson => Ok(()),
+        };
+        if let Err(e) = header {
+            drop(writer);
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+
+        Ok(Self {
+            format,
+            target,
+            temp_path,
+            writer,
+            written: 0,
+        })
+    }
+
+    pub fn write_items(&mut self, items: &[LibraryMedia]) -> std::io::Result<()> {
+        match self.format {
+            ExportFormat::Json => {
+                for (index, item) in items.iter().enumerate() {
+                    // Match JSON.stringify(items, null, 2): every line of an item is nested one level
+                    let pretty = serde_json::to_string_pretty(&export_value(item))?;
+                    let separator = if self.written + index == 0 { "" } else { "," };
+                    write!(self.writer, "{}\n  {}", separator, pretty.replace('\n', "\n  "))?;
+                }
+            }
+            ExportFormat::Ndjson => {
+                for item in items {
+                    serde_json::to_writer(&mut self.writer, &export_value(item))?;
+                    self.writer.write_all(b"\n")?;
+                }
+            }
+            ExportFormat::Csv => write_csv(&mut self.writer, items.iter().map(csv_record))?,
+        }
+        self.written += items.len();
+        Ok(())
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Flush and fsync the temp file, back up the target, then rename the temp file over it
+    pub fn finish(self, keep_backups: usize) -> AppResult<ExportResult> {
+        let ExportWriter {
+            format,
+            target,
+            temp_path,
+            writer,
+            written,
+        } = self;
+
+        let result = close_file(format, writer, written)
+            .and_then(|bytes_written| {
+                let rotated = rotate_backups(&target, keep_backups)?;
+                fs::rename(&temp_path, &target)?;
+                sync_parent_dir(&target);
+                Ok((bytes_written, rotated))
+            })
+            .map_err(AppError::from);
+
+        match result {
+            Ok((bytes_written, rotated)) => Ok(ExportResult {
+                path: target.to_string_lossy().into_owned(),
+                items_written: written,
+                bytes_written,
+                rotated: rotated
+                    .iter()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .collect(),
+            }),
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Drop the export and delete the temp file, leaving the target as it was
+    pub fn abort(self) {
+        let temp_path = self.temp_path.clone();
+        drop(self);
+        let _ = fs::remove_file(temp_path);
+    }
+}
+
+/// Write the trailer and fsync; the handle is closed on return so the file can be renamed on Windows
+fn close_file(
+    format: ExportFormat,
+    mut writer: BufWriter<File>,
+    written: usize,
+) -> std::io::Result<u64> {
+    if format == ExportFormat::Json {
+        let closing = if written == 0 { "]\n" } else { "\n]\n" };
+        writer.write_all(closing.as_bytes())?;
+    }
+
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    Ok(file.metadata()?.len())
+}
+
+fn resolve_target(path: &Path, format: ExportFormat) -> PathBuf {
+    if path.is_dir() {
+        let date = chrono::Local::now().format("%Y-%m-%d");
+        path.join(format!("watchfolio-{}.{}", date, format.extension()))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// The temp file lives in the target's directory so the final rename never crosses filesystems.
+/// The counter keeps concurrent exports to the same target from sharing one.
+fn temp_path_for(target: &Path) -> PathBuf {
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "watchfolio-export".into());
+    let n = NEXT_TEMP.fetch_add(1, Ordering::Relaxed);
+    target.with_file_name(format!(".{}.{}.{}.tmp", file_name, std::process::id(), n))
+}
+
+fn backup_path(target: &Path, index: usize) -> PathBuf {
+    let mut name = target.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Shift `target.1` to `target.2`, ... keeping at most `keep` copies, and copy `target` to `target.1`.
+/// `target` itself stays in place until the new export is renamed over it, so it is never missing.
+fn rotate_backups(target: &Path, keep: usize) -> std::io::Result<Vec<PathBuf>> {
+    if keep == 0 || !target.exists() {
+        return Ok(Vec::new());
+    }
+
+    let oldest = backup_path(target, keep);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+
+    let mut rotated = Vec::new();
+    for index in (1..keep).rev() {
+        let from = backup_path(target, index);
+        if from.exists() {
+            let to = backup_path(target, index + 1);
+            fs::rename(&from, &to)?;
+            rotated.push(to);
+        }
+    }
+
+    // A hard link costs nothing; copy where the filesystem has none
+    let first = backup_path(target, 1);
+    if fs::hard_link(target, &first).is_err() {
+        fs::copy(target, &first)?;
+    }
+    rotated.push(first);
+    rotated.reverse();
+
+    Ok(rotated)
+}
+
+/// Persist the rename itself; directories can't be opened this way on Windows
+fn sync_parent_dir(target: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = target.parent() {
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = target;
+}
+
+/// Strips the fields that only make sense inside a user's own database
+fn export_value(item: &LibraryMedia) -> serde_json::Value {
+    let mut value = serde_json::to_value(item).unwrap_or(serde_json::Value::Null);
+    if let Some(object) = value.as_object_mut() {
+        for field in EXCLUDED_FIELDS {
+            object.remove(field);
+        }
+    }
+    value
+}
+
+/// Write records through the file's buffer, flushing the csv writer's own so nothing is lost when it drops
+fn write_csv<I>(writer: &mut BufWriter<File>, records: I) -> std::io::Result<()>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::Item: AsRef<[u8]>,
+{
+    let mut csv = csv::Writer::from_writer(writer);
+    for record in records {
+        csv.write_record(record)?;
+    }
+    csv.flush()
+}
+
+fn csv_record(item: &LibraryMedia) -> [String; 17] {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+
+    [
+        item.id.clone(),
+        item.tmdb_id.to_string(),
+        item.media_type.as_str().to_string(),
+        item.title.clone(),
+        optional(item.poster_path.clone()),
+        optional(item.release_date.clone()),
+        item.status.as_str().to_string(),
+        item.is_favorite.to_string(),
+        optional(item.user_rating.map(|r| r.to_string())),
+        item.added_at.clone(),
+        item.last_updated_at.clone(),
+        optional(item.notes.clone()),
+        optional(item.overview.clone()),
+        serde_json::to_string(&item.genres).unwrap_or_default(),
+        serde_json::to_string(&item.networks).unwrap_or_default(),
+        optional(item.rating.map(|r| r.to_string())),
+        optional(item.total_minutes_runtime.map(|r| r.to_string())),
+    ]
+}
+
+/// Open exports, keyed by the id handed back from `export_begin`. The map is only locked to look
+/// a session up; each session has its own lock, held on the blocking pool while its file is written.
+#[derive(Default)]
+pub struct ExportSessions {
+    next_id: AtomicU64,
+    sessions: Mutex<HashMap<u64, Arc<Mutex<ExportSession>>>>,
+}
+
+struct ExportSession {
+    total: Option<usize>,
+    keep_backups: usize,
+    /// When the export was opened or last written to
+    last_active: Instant,
+    /// Taken when the export is finished, cancelled, expires or fails
+    writer: Option<ExportWriter>,
+}
+
+impl ExportSessions {
+    fn get(&self, export_id: u64) -> AppResult<Arc<Mutex<ExportSession>>> {
+        self.sessions
+            .lock()?
+            .get(&export_id)
+            .cloned()
+            .ok_or_else(|| unknown_export(export_id))
+    }
+
+    fn remove(&self, export_id: u64) -> AppResult<Option<Arc<Mutex<ExportSession>>>> {
+        Ok(self.sessions.lock()?.remove(&export_id))
+    }
+
+    /// Create the temp file and register the export
+    fn begin(&self, options: ExportOptions, now: Instant) -> AppResult<u64> {
+        let writer = ExportWriter::create(&options.path, options.format)?;
+
+        let export_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.sessions.lock()?.insert(
+            export_id,
+            Arc::new(Mutex::new(ExportSession {
+                total: options.total,
+                keep_backups: options.keep_backups,
+                last_active: now,
+                writer: Some(writer),
+            })),
+        );
+        Ok(export_id)
+    }
+
+    /// Append items, returning how many have been written so far and the expected total.
+    /// A failed write abandons the export.
+    fn write(&self, export_id: u64, items: &[LibraryMedia], now: Instant) -> AppResult<(usize, Option<usize>)> {
+        let session = self.get(export_id)?;
+        let mut session = session.lock()?;
+        let total = session.total;
+        let writer = session.writer.as_mut().ok_or_else(|| unknown_export(export_id))?;
+        match writer.write_items(items) {
+            Ok(()) => {
+                let written = writer.written();
+                session.last_active = now;
+                Ok((written, total))
+            }
+            Err(e) => {
+                if let Some(writer) = session.writer.take() {
+                    writer.abort();
+                }
+                drop(session);
+                self.remove(export_id)?;
+                Err(AppError::from(e))
+            }
+        }
+    }
+
+    fn finish(&self, export_id: u64) -> AppResult<ExportResult> {
+        let session = self.remove(export_id)?.ok_or_else(|| unknown_export(export_id))?;
+        let mut session = session.lock()?;
+        let writer = session.writer.take().ok_or_else(|| unknown_export(export_id))?;
+        if writer.written() == 0 {
+            writer.abort();
+            return Err(AppError::invalid_input("No items were provided to export."));
+        }
+        writer.finish(session.keep_backups)
+    }
+
+    /// Delete the temp file; cancelling an export that is already gone is not an error
+    fn cancel(&self, export_id: u64) -> AppResult<()> {
+        if let Some(session) = self.remove(export_id)? {
+            if let Some(writer) = session.lock()?.writer.take() {
+                writer.abort();
+            }
+        }
+        Ok(())
+    }
+
+    /// Cancel the export if it has been idle for `SESSION_IDLE_TIMEOUT`. Returns how long it has
+    /// left otherwise, or `None` once it is gone.
+    fn expire_if_idle(&self, export_id: u64, now: Instant) -> AppResult<Option<Duration>> {
+        let Some(session) = self.sessions.lock()?.get(&export_id).cloned() else {
+            return Ok(None);
+        };
+        let idle = now.saturating_duration_since(session.lock()?.last_active);
+        if idle < SESSION_IDLE_TIMEOUT {
+            return Ok(Some(SESSION_IDLE_TIMEOUT - idle));
+        }
+
+        log::warn!("Export {} was abandoned after {}s without a chunk", export_id, idle.as_secs());
+        self.cancel(export_id)?;
+        Ok(None)
+    }
+}
+
+/// Run `f` against the open exports on the blocking pool, where their files are written
+async fn with_sessions<T: Send + 'static>(
+    app: &AppHandle,
+    f: impl FnOnce(&ExportSessions) -> AppResult<T> + Send + 'static,
+) -> AppResult<T> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || f(&app.state::<ExportSessions>())).await?
+}
+
+/// Cancel the export once it goes `SESSION_IDLE_TIMEOUT` without a chunk, so a webview that
+/// reloads or crashes mid-export doesn't leave its temp file behind until the app exits
+fn expire_when_idle(app: &AppHandle, export_id: u64) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut wait = SESSION_IDLE_TIMEOUT;
+        loop {
+            tokio::time::sleep(wait).await;
+            match with_sessions(&app, move |sessions| sessions.expire_if_idle(export_id, Instant::now())).await {
+                Ok(Some(left)) => wait = left,
+                _ => break,
+            }
+        }
+    });
+}
+
+fn emit_progress(app: &AppHandle, export_id: u64, written: usize, total: Option<usize>) {
+    let _ = app.emit(
+        "export:progress",
+        ExportProgress {
+            export_id,
+            written,
+            total,
+        },
+    );
+}
+
+/// Open an export and return an id for streaming chunks into it
+#[tauri::command]
+pub async fn export_begin(app: AppHandle, options: ExportOptions) -> AppResult<u64> {
+    let export_id = with_sessions(&app, move |sessions| sessions.begin(options, Instant::now())).await?;
+    expire_when_idle(&app, export_id);
+    Ok(export_id)
+}
+
+/// Append a chunk of library items to an open export
+#[tauri::command]
+pub async fn export_chunk(app: AppHandle, export_id: u64, items: Vec<LibraryMedia>) -> AppResult<usize> {
+    let (written, total) =
+        with_sessions(&app, move |sessions| sessions.write(export_id, &items, Instant::now())).await?;
+    emit_progress(&app, export_id, written, total);
+    Ok(written)
+}
+
+/// Atomically move a finished export into place
+#[tauri::command]
+pub async fn export_finish(app: AppHandle, export_id: u64) -> AppResult<ExportResult> {
+    with_sessions(&app, move |sessions| sessions.finish(export_id)).await
+}
+
+/// Abandon an export, leaving any previous file at the target untouched
+#[tauri::command]
+pub async fn export_cancel(app: AppHandle, export_id: u64) -> AppResult<()> {
+    with_sessions(&app, move |sessions| sessions.cancel(export_id)).await
+}
+
+/// Export a whole library in one call
+#[tauri::command]
+pub async fn export_data(
+    app: AppHandle,
+    options: ExportOptions,
+    items: Vec<LibraryMedia>,
+) -> AppResult<ExportResult> {
+    let export_id = export_begin(app.clone(), options).await?;
+    export_chunk(app.clone(), export_id, items).await?;
+    export_finish(app, export_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::{MediaType, WatchStatus};
+
+    /// A fresh directory under the system temp dir, removed on drop
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            static NEXT: AtomicU64 = AtomicU64::new(0);
+            let n = NEXT.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!("watchfolio-{}-{}-{}", name, std::process::id(), n));
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn join(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+
+        /// Names of the files in the directory, sorted
+        fn files(&self) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(&self.0)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn item(id: &str, title: &str) -> LibraryMedia {
+        LibraryMedia {
+            id: id.to_string(),
+            status: WatchStatus::Completed,
+            is_favorite: true,
+            user_rating: Some(9.0),
+            notes: Some("Line one\nwith \"quotes\", and a comma".into()),
+            added_at: "2025-01-01T00:00:00.000Z".into(),
+            last_updated_at: "2025-01-02T00:00:00.000Z".into(),
+            tmdb_id: 603,
+            media_type: MediaType::Movie,
+            title: title.to_string(),
+            overview: None,
+            poster_path: Some("/poster.jpg".into()),
+            release_date: Some("1999-03-31".into()),
+            genres: vec![28, 878],
+            rating: Some(8.2),
+            total_minutes_runtime: Some(136),
+            networks: Vec::new(),
+            library: Some("library-id".into()),
+            user_id: Some("user-id".into()),
+        }
+    }
+
+    fn export(target: &Path, format: ExportFormat, title: &str, keep_backups: usize) -> AppResult<ExportResult> {
+        let mut writer = ExportWriter::create(&target.to_string_lossy(), format)?;
+        writer.write_items(&[item("a", title)])?;
+        writer.finish(keep_backups)
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn rotation_keeps_the_requested_number_of_backups() {
+        let dir = TempDir::new("rotate");
+        let target = dir.join("library.ndjson");
+        for title in ["First", "Second", "Third", "Fourth"] {
+            export(&target, ExportFormat::Ndjson, title, 2).unwrap();
+        }
+
+        assert_eq!(dir.files(), ["library.ndjson", "library.ndjson.1", "library.ndjson.2"]);
+        assert!(read(&target).contains("Fourth"));
+        assert!(read(&backup_path(&target, 1)).contains("Third"));
+        assert!(read(&backup_path(&target, 2)).contains("Second"));
+    }
+
+    #[test]
+    fn rotation_reports_the_backups_newest_first() {
+        let dir = TempDir::new("rotated");
+        let target = dir.join("library.json");
+        assert!(export(&target, ExportFormat::Json, "First", 3).unwrap().rotated.is_empty());
+        export(&target, ExportFormat::Json, "Second", 3).unwrap();
+
+        let result = export(&target, ExportFormat::Json, "Third", 3).unwrap();
+        let expected: Vec<String> = [1, 2]
+            .into_iter()
+            .map(|index| backup_path(&target, index).to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(result.rotated, expected);
+    }
+
+    #[test]
+    fn keep_zero_replaces_the_target_without_backups() {
+        let dir = TempDir::new("keep-zero");
+        let target = dir.join("library.csv");
+        export(&target, ExportFormat::Csv, "First", 0).unwrap();
+        let result = export(&target, ExportFormat::Csv, "Second", 0).unwrap();
+
+        assert!(result.rotated.is_empty());
+        assert_eq!(dir.files(), ["library.csv"]);
+        assert!(read(&target).contains("Second"));
+    }
+
+    #[test]
+    fn failed_finish_leaves_the_original_in_place() {
+        let dir = TempDir::new("failed");
+        let target = dir.join("library.json");
+        export(&target, ExportFormat::Json, "Original", 0).unwrap();
+
+        // A directory where the oldest backup goes can't be removed as a file, so rotation fails
+        fs::create_dir(backup_path(&target, 2)).unwrap();
+        assert!(export(&target, ExportFormat::Json, "Replacement", 2).is_err());
+
+        assert!(read(&target).contains("Original"));
+        assert!(!dir.files().iter().any(|name| name.ends_with(".tmp")));
+    }
+
+    #[test]
+    fn abort_leaves_the_original_in_place() {
+        let dir = TempDir::new("abort");
+        let target = dir.join("library.json");
+        export(&target, ExportFormat::Json, "Original", 0).unwrap();
+
+        let mut writer = ExportWriter::create(&target.to_string_lossy(), ExportFormat::Json).unwrap();
+        writer.write_items(&[item("a", "Replacement")]).unwrap();
+        writer.abort();
+
+        assert_eq!(dir.files(), ["library.json"]);
+        assert!(read(&target).contains("Original"));
+    }
+
+    fn options(target: &Path, format: ExportFormat) -> ExportOptions {
+        ExportOptions {
+            path: target.to_string_lossy().into_owned(),
+            format,
+            total: Some(3),
+            keep_backups: 0,
+        }
+    }
+
+    fn write_all(target: &Path, format: ExportFormat, items: &[LibraryMedia]) {
+        let mut writer = ExportWriter::create(&target.to_string_lossy(), format).unwrap();
+        for chunk in items.chunks(1) {
+            writer.write_items(chunk).unwrap();
+        }
+        writer.finish(0).unwrap();
+    }
+
+    #[test]
+    fn json_matches_json_stringify_and_reads_back() {
+        let dir = TempDir::new("json");
+        let target = dir.join("library.json");
+        let items = [item("a", "The Matrix"), item("b", "Heat")];
+        write_all(&target, ExportFormat::Json, &items);
+
+        let values: Vec<serde_json::Value> = items.iter().map(export_value).collect();
+        let content = read(&target);
+        assert_eq!(content, serde_json::to_string_pretty(&values).unwrap() + "\n");
+
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed, values);
+        for field in EXCLUDED_FIELDS {
+            assert!(parsed[0].get(field).is_none());
+        }
+    }
+
+    #[test]
+    fn json_without_items_is_an_empty_array() {
+        let dir = TempDir::new("json-empty");
+        let target = dir.join("library.json");
+        write_all(&target, ExportFormat::Json, &[]);
+        assert_eq!(read(&target), "[]\n");
+    }
+
+    #[test]
+    fn csv_quotes_fields_and_reads_back() {
+        let dir = TempDir::new("csv");
+        let target = dir.join("library.csv");
+        let items = [item("a", "Crouching Tiger, Hidden Dragon"), item("b", "Heat")];
+        write_all(&target, ExportFormat::Csv, &items);
+
+        let mut reader = csv::Reader::from_path(&target).unwrap();
+        assert_eq!(reader.headers().unwrap(), &csv::StringRecord::from(CSV_HEADERS.to_vec()));
+        let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(&records[0][3], "Crouching Tiger, Hidden Dragon");
+        assert_eq!(&records[0][11], "Line one\nwith \"quotes\", and a comma");
+        assert_eq!(&records[0][13], "[28,878]");
+        assert_eq!(&records[0][12], "");
+        assert_eq!(&records[1][0], "b");
+    }
+
+    #[test]
+    fn ndjson_writes_one_item_per_line() {
+        let dir = TempDir::new("ndjson");
+        let target = dir.join("library.ndjson");
+        let items = [item("a", "The Matrix"), item("b", "Heat")];
+        write_all(&target, ExportFormat::Ndjson, &items);
+
+        let content = read(&target);
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, item) in lines.iter().zip(&items) {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value, export_value(item));
+            assert!(value.get("userId").is_none());
+        }
+    }
+
+    #[test]
+    fn a_directory_target_gets_a_dated_file() {
+        let dir = TempDir::new("dated");
+        write_all(&dir.0, ExportFormat::Ndjson, &[item("a", "Heat")]);
+
+        let date = chrono::Local::now().format("%Y-%m-%d");
+        assert_eq!(dir.files(), [format!("watchfolio-{}.ndjson", date)]);
+    }
+
+    #[test]
+    fn session_writes_chunks_and_moves_the_file_into_place() {
+        let dir = TempDir::new("session");
+        let target = dir.join("library.ndjson");
+        let sessions = ExportSessions::default();
+        let now = Instant::now();
+
+        let export_id = sessions.begin(options(&target, ExportFormat::Ndjson), now).unwrap();
+        assert_eq!(sessions.write(export_id, &[item("a", "One"), item("b", "Two")], now).unwrap(), (2, Some(3)));
+        assert_eq!(sessions.write(export_id, &[item("c", "Three")], now).unwrap(), (3, Some(3)));
+        assert!(!target.exists());
+
+        let result = sessions.finish(export_id).unwrap();
+        assert_eq!(result.items_written, 3);
+        assert_eq!(result.bytes_written, fs::metadata(&target).unwrap().len());
+        assert_eq!(read(&target).lines().count(), 3);
+        assert_eq!(dir.files(), ["library.ndjson"]);
+
+        assert!(sessions.write(export_id, &[item("d", "Four")], now).is_err());
+        assert!(sessions.finish(export_id).is_err());
+    }
+
+    #[test]
+    fn finishing_an_empty_session_fails_and_cleans_up() {
+        let dir = TempDir::new("session-empty");
+        let sessions = ExportSessions::default();
+        let export_id = sessions.begin(options(&dir.join("library.json"), ExportFormat::Json), Instant::now()).unwrap();
+
+        assert!(sessions.finish(export_id).is_err());
+        assert!(dir.files().is_empty());
+    }
+
+    #[test]
+    fn cancel_removes_the_temp_file() {
+        let dir = TempDir::new("cancel");
+        let target = dir.join("library.csv");
+        export(&target, ExportFormat::Csv, "Original", 0).unwrap();
+
+        let sessions = ExportSessions::default();
+        let now = Instant::now();
+        let export_id = sessions.begin(options(&target, ExportFormat::Csv), now).unwrap();
+        sessions.write(export_id, &[item("a", "Replacement")], now).unwrap();
+        assert!(dir.files().iter().any(|name| name.ends_with(".tmp")));
+
+        sessions.cancel(export_id).unwrap();
+        assert_eq!(dir.files(), ["library.csv"]);
+        assert!(read(&target).contains("Original"));
+
+        sessions.cancel(export_id).unwrap();
+        assert!(sessions.write(export_id, &[item("a", "Replacement")], now).is_err());
+    }
+
+    #[test]
+    fn idle_sessions_expire() {
+        let dir = TempDir::new("expire");
+        let sessions = ExportSessions::default();
+        let start = Instant::now();
+        let minutes = |m: u64| start + Duration::from_secs(m * 60);
+
+        let export_id = sessions.begin(options(&dir.join("library.json"), ExportFormat::Json), start).unwrap();
+        assert_eq!(sessions.expire_if_idle(export_id, minutes(1)).unwrap(), Some(Duration::from_secs(4 * 60)));
+
+        // A chunk restarts the clock
+        sessions.write(export_id, &[item("a", "Heat")], minutes(3)).unwrap();
+        assert_eq!(sessions.expire_if_idle(export_id, minutes(6)).unwrap(), Some(Duration::from_secs(2 * 60)));
+
+        assert_eq!(sessions.expire_if_idle(export_id, minutes(8)).unwrap(), None);
+        assert!(dir.files().is_empty());
+        assert!(sessions.write(export_id, &[item("b", "Ronin")], minutes(8)).is_err());
+        assert_eq!(sessions.expire_if_idle(export_id, minutes(9)).unwrap(), None);
+    }
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::{AppError, AppResult};
+use crate::import::{self, ImportResult};
+use crate::importers::ImportSource;
+use crate::library::LibraryStore;
+
+/// The last parsed drop, waiting for the user to confirm the import; a new drop replaces it
+#[derive(Default)]
+pub struct DroppedImports {
+    next_id: AtomicU64,
+    latest: Mutex<Option<(u64, Vec<ImportResult>)>>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DroppedFile {
+    path: String,
+    source: Option<ImportSource>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportPreview {
+    preview_id: u64,
+    files: Vec<DroppedFile>,
+    total_items: usize,
+    new_items: usize,
+    updated_items: usize,
+    /// Items whose library copy was changed more recently than the copy being imported
+    conflicting_items: usize,
+    invalid_items: usize,
+    unresolved_items: usize,
+}
+
+/// Parse files dropped onto the main window off the event loop and emit `import:preview`
+pub fn handle_drop(app: &AppHandle, paths: Vec<PathBuf>) {
+    let paths: Vec<PathBuf> = paths.into_iter().filter(|p| p.is_file()).collect();
+    if paths.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let index = app
+            .try_state::<LibraryStore>()
+            .and_then(|store| store.index().ok())
+            .unwrap_or_default();
+        let existing_ids: HashSet<String> = index.keys().cloned().collect();
+
+        let mut files = Vec::new();
+        let mut results = Vec::new();
+        for path in paths {
+            let parsed = import::read_import(&path).map_err(|e| e.message).and_then(|content| import::import_content(&path, &content, None, None, &existing_ids));
+
+            match parsed {
+                Ok(result) => {
+                    files.push(DroppedFile {
+                        path: path.to_string_lossy().into_owned(),
+                        source: Some(result.source),
+                        error: None,
+                    });
+                    results.push(result);
+                }
+                Err(e) => files.push(DroppedFile {
+                    path: path.to_string_lossy().into_owned(),
+                    source: None,
+                    error: Some(e),
+                }),
+            }
+        }
+
+        let mut preview = summarize(&results, &index);
+        preview.files = files;
+
+        if let Some(dropped) = app.try_state::<DroppedImports>() {
+            preview.preview_id = dropped.next_id.fetch_add(1, Ordering::Relaxed);
+            if let Ok(mut latest) = dropped.latest.lock() {
+                *latest = Some((preview.preview_id, results));
+            }
+        }
+
+        let _ = app.emit_to("main", "import:preview", preview);
+    });
+}
+
+fn summarize(results: &[ImportResult], index: &HashMap<String, String>) -> ImportPreview {
+    let mut preview = ImportPreview {
+        preview_id: 0,
+        files: Vec::new(),
+        total_items: 0,
+        new_items: 0,
+        updated_items: 0,
+        conflicting_items: 0,
+        invalid_items: 0,
+        unresolved_items: 0,
+    };
+
+    for result in results {
+        preview.total_items += result.total_items;
+        preview.invalid_items += result.errors.len();
+        preview.unresolved_items += result.unresolved.len();
+
+        for item in &result.items {
+            match index.get(&item.id) {
+                None => preview.new_items += 1,
+                Some(library_updated_at) if is_newer(library_updated_at, &item.last_updated_at) => {
+                    preview.conflicting_items += 1
+                }
+                Some(_) => preview.updated_items += 1,
+            }
+        }
+    }
+
+    preview
+}
+
+fn is_newer(a: &str, b: &str) -> bool {
+    let parse = |s: &str| import::parse_date(&serde_json::Value::String(s.to_string()));
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a > b,
+        _ => false,
+    }
+}
+
+/// Hand the parsed items of a previewed drop to the frontend so it can apply them
+#[tauri::command]
+pub fn take_dropped_import(
+    dropped: State<DroppedImports>,
+    preview_id: u64,
+) -> AppResult<Vec<ImportResult>> {
+    let mut latest = dropped.latest.lock()?;
+    match latest.take() {
+        Some((id, results)) if id == preview_id => Ok(results),
+        other => {
+            *latest = other;
+            Err(AppError::not_found(format!(
+                "Import preview {} was replaced by a newer drop or already imported",
+                preview_id
+            )))
+        }
+    }
+}
+
+/// Forget a previewed drop the user decided not to import
+#[tauri::command]
+pub fn discard_dropped_import(dropped: State<DroppedImports>, preview_id: u64) -> AppResult<()> {
+    let mut latest = dropped.latest.lock()?;
+    if latest.as_ref().is_some_and(|(id, _)| *id == preview_id) {
+        *latest = None;
+    }
+    Ok(())
+}
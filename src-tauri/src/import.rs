@@ -0,0 +1,887 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::error::{AppError, AppResult};
+use crate::importers::{self, ImportSource, UnresolvedItem};
+use crate::media::{LibraryMedia, MediaType, WatchStatus};
+
+/// Same limit as `LIBRARY_IMPORT_MAX_SIZE` in `src/config/app.ts`
+pub const MAX_IMPORT_SIZE: u64 = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Json,
+    Csv,
+}
+
+impl ImportFormat {
+    /// Guess the format from the file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "json" => Some(ImportFormat::Json),
+            "csv" => Some(ImportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    pub path: String,
+    /// Detected from the file contents when omitted
+    pub source: Option<ImportSource>,
+    /// Detected from the file extension when omitted; only used for Watchfolio files
+    pub format: Option<ImportFormat>,
+    /// Ids already in the user's library, used to split new items from updates
+    #[serde(default)]
+    pub existing_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Every problem found in one row; `row` is 1-based like the frontend's "item #N"
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowError {
+    pub row: usize,
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub source: ImportSource,
+    pub items: Vec<LibraryMedia>,
+    pub errors: Vec<RowError>,
+    /// Titles from other trackers that have no TMDB id yet and need to be matched
+    pub unresolved: Vec<UnresolvedItem>,
+    /// Watch history carried over from other trackers, keyed by item id
+    pub watch_dates: HashMap<String, Vec<String>>,
+    pub total_items: usize,
+    pub movies: usize,
+    pub tv_shows: usize,
+    pub new_items: usize,
+    pub updated_items: usize,
+}
+
+impl ImportResult {
+    pub fn new(
+        source: ImportSource,
+        items: Vec<LibraryMedia>,
+        errors: Vec<RowError>,
+        existing_ids: &HashSet<String>,
+    ) -> Self {
+        let movies = items
+            .iter()
+            .filter(|item| item.media_type == MediaType::Movie)
+            .count();
+        let updated_items = items
+            .iter()
+            .filter(|item| existing_ids.contains(&item.id))
+            .count();
+
+        Self {
+            source,
+            total_items: items.len(),
+            movies,
+            tv_shows: items.len() - movies,
+            new_items: items.len() - updated_items,
+            updated_items,
+            items,
+            errors,
+            unresolved: Vec::new(),
+            watch_dates: HashMap::new(),
+        }
+    }
+}
+
+/// A parsed-but-unvalidated row, keyed by column or property name
+pub type RawRow = Map<String, Value>;
+
+/// Format a date the way `Date.prototype.toISOString` does
+pub fn to_iso_string(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Lenient date parsing covering the formats `new Date(...)` accepts in practice
+pub fn parse_date(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Number(n) => Utc.timestamp_millis_opt(n.as_f64()? as i64).single(),
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(date) = DateTime::parse_from_rfc3339(s) {
+                return Some(date.with_timezone(&Utc));
+            }
+            if let Ok(date) = DateTime::parse_from_rfc2822(s) {
+                return Some(date.with_timezone(&Utc));
+            }
+            for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"] {
+                if let Ok(date) = NaiveDateTime::parse_from_str(s, format) {
+                    return Some(date.and_utc());
+                }
+            }
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc())
+        }
+        _ => None,
+    }
+}
+
+/// `Number(value)` semantics: blank strings are 0, anything unparsable is NaN
+fn coerce_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) if s.trim().is_empty() => Some(0.0),
+        Value::String(s) => s.trim().parse::<f64>().ok().filter(|n| !n.is_nan()),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        Value::Null => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Collects field errors for a single row, mirroring `RawMediaSchema` in `import.worker.ts`.
+///
+/// Where zod's coercion would store bad data, this is deliberately stricter:
+/// - `isFavorite` reads "false", "0" and "no" as false and rejects other strings, objects and
+///   arrays; `z.coerce.boolean` makes any non-empty string true, so every re-imported CSV row
+///   would come back as a favorite
+/// - `tmdbId` and the `genres`/`networks` ids must be whole numbers, as they are stored as integers
+/// - a `null` `addedAt` or `lastUpdatedAt` is left unset, where `new Date(null)` would give 1970
+struct RowValidator<'a> {
+    row: &'a RawRow,
+    errors: Vec<FieldError>,
+}
+
+impl<'a> RowValidator<'a> {
+    fn new(row: &'a RawRow) -> Self {
+        Self {
+            row,
+            errors: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Missing and `null` are treated alike for nullable fields
+    fn present(&self, field: &str) -> Option<&'a Value> {
+        self.row.get(field).filter(|value| !value.is_null())
+    }
+
+    /// For fields the schema makes optional but not nullable: missing is fine, `null` is not
+    fn optional(&mut self, field: &str, expected: &str) -> Option<&'a Value> {
+        match self.row.get(field) {
+            Some(Value::Null) => {
+                self.error(field, expected);
+                None
+            }
+            value => value,
+        }
+    }
+
+    fn string_value(&mut self, field: &str, value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            _ => {
+                self.error(field, "must be a string");
+                None
+            }
+        }
+    }
+
+    fn string(&mut self, field: &str) -> Option<String> {
+        let value = self.present(field)?;
+        self.string_value(field, value)
+    }
+
+    fn non_null_string(&mut self, field: &str) -> Option<String> {
+        let value = self.optional(field, "must be a string")?;
+        self.string_value(field, value)
+    }
+
+    fn number(&mut self, field: &str) -> Option<f64> {
+        let value = self.present(field)?;
+        match coerce_number(value) {
+            Some(n) => Some(n),
+            None => {
+                self.error(field, "must be a number");
+                None
+            }
+        }
+    }
+
+    fn tmdb_id(&mut self) -> Option<u64> {
+        let Some(value) = self.row.get("tmdbId") else {
+            self.error("tmdbId", "must be a number");
+            return None;
+        };
+        match coerce_number(value) {
+            None => {
+                self.error("tmdbId", "must be a number");
+                None
+            }
+            Some(n) if n <= 0.0 => {
+                self.error("tmdbId", "must be a positive number");
+                None
+            }
+            Some(n) if n.fract() != 0.0 => {
+                self.error("tmdbId", "must be an integer");
+                None
+            }
+            Some(n) => Some(n as u64),
+        }
+    }
+
+    fn media_type(&mut self) -> Option<MediaType> {
+        match self
+            .row
+            .get("media_type")
+            .and_then(Value::as_str)
+            .and_then(MediaType::parse)
+        {
+            Some(media_type) => Some(media_type),
+            None => {
+                self.error("media_type", "must be either \"movie\" or \"tv\"");
+                None
+            }
+        }
+    }
+
+    fn title(&mut self) -> Option<String> {
+        let title = self.non_null_string("title")?.trim().to_string();
+        if title.is_empty() {
+            self.error("title", "cannot be empty");
+            return None;
+        }
+        Some(title)
+    }
+
+    fn status(&mut self) -> WatchStatus {
+        const EXPECTED: &str =
+            "must be one of \"watching\", \"willWatch\", \"onHold\", \"dropped\", \"none\" or \"completed\"";
+        let Some(value) = self.optional("status", EXPECTED) else {
+            return WatchStatus::None;
+        };
+        match value.as_str().and_then(WatchStatus::parse) {
+            Some(status) => status,
+            None => {
+                self.error("status", EXPECTED);
+                WatchStatus::None
+            }
+        }
+    }
+
+    fn boolean(&mut self, field: &str) -> bool {
+        let Some(value) = self.present(field) else {
+            return false;
+        };
+        match value {
+            Value::Bool(b) => *b,
+            Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+            Value::String(s) => match s.trim().to_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" | "" => false,
+                _ => {
+                    self.error(field, "must be true or false");
+                    false
+                }
+            },
+            _ => {
+                self.error(field, "must be true or false");
+                false
+            }
+        }
+    }
+
+    fn user_rating(&mut self) -> Option<f64> {
+        let rating = self.number("userRating")?;
+        if !(1.0..=10.0).contains(&rating) {
+            self.error("userRating", "must be between 1 and 10");
+            return None;
+        }
+        Some(rating)
+    }
+
+    fn integer(&mut self, field: &str) -> Option<i64> {
+        let n = self.number(field)?;
+        if n.fract() != 0.0 {
+            self.error(field, "must be an integer");
+            return None;
+        }
+        Some(n as i64)
+    }
+
+    fn date(&mut self, field: &str) -> Option<DateTime<Utc>> {
+        let value = self.present(field)?;
+        match parse_date(value) {
+            Some(date) => Some(date),
+            None => {
+                self.error(field, "is not a valid date");
+                None
+            }
+        }
+    }
+
+    /// `genres`/`networks` arrive as arrays in JSON and as JSON strings in CSV
+    fn id_list(&mut self, field: &str) -> Vec<u32> {
+        let Some(value) = self.optional(field, "must be an array of numbers") else {
+            return Vec::new();
+        };
+        let parsed;
+        let value = match value {
+            Value::String(s) => match serde_json::from_str::<Value>(s) {
+                Ok(v) => {
+                    parsed = v;
+                    &parsed
+                }
+                Err(_) => {
+                    self.error(field, "must be a JSON array of numbers");
+                    return Vec::new();
+                }
+            },
+            other => other,
+        };
+
+        let ids = value.as_array().and_then(|items| {
+            items
+                .iter()
+                .map(|item| item.as_u64().and_then(|n| u32::try_from(n).ok()))
+                .collect::<Option<Vec<_>>>()
+        });
+        match ids {
+            Some(ids) => ids,
+            None => {
+                self.error(field, "must be an array of numbers");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Validate one raw row and fill in defaults the same way `FinalMediaSchema` does
+pub fn validate_row(row: &RawRow, now: &DateTime<Utc>) -> Result<LibraryMedia, Vec<FieldError>> {
+    let mut v = RowValidator::new(row);
+
+    let id = v.non_null_string("id").filter(|id| !id.is_empty());
+    let tmdb_id = v.tmdb_id();
+    let media_type = v.media_type();
+    let title = v.title();
+    let status = v.status();
+    let is_favorite = v.boolean("isFavorite");
+    let user_rating = v.user_rating();
+    let notes = v.string("notes");
+    let added_at = v.date("addedAt");
+    let last_updated_at = v.date("lastUpdatedAt");
+    let release_date = v.date("releaseDate");
+    let poster_path = v.string("posterPath");
+    let genres = v.id_list("genres");
+    let rating = v.number("rating");
+    let total_minutes_runtime = v.integer("totalMinutesRuntime");
+    let networks = v.id_list("networks");
+    let overview = v.string("overview");
+    let user_id = v.string("userId");
+
+    let (Some(tmdb_id), Some(media_type), true) = (tmdb_id, media_type, v.errors.is_empty()) else {
+        return Err(v.errors);
+    };
+
+    let key = format!("{}-{}", media_type.as_str(), tmdb_id);
+    Ok(LibraryMedia {
+        id: id.unwrap_or_else(|| key.clone()),
+        status,
+        is_favorite,
+        user_rating,
+        notes,
+        added_at: to_iso_string(&added_at.unwrap_or(*now)),
+        last_updated_at: to_iso_string(&last_updated_at.unwrap_or(*now)),
+        tmdb_id,
+        media_type,
+        title: title.unwrap_or_else(|| format!("Untitled {}", key)),
+        overview,
+        poster_path,
+        release_date: release_date.as_ref().map(to_iso_string),
+        genres,
+        rating,
+        total_minutes_runtime,
+        networks,
+        library: None,
+        user_id,
+    })
+}
+
+/// Validate every row, keeping the good ones and reporting the rest
+pub fn validate_rows(rows: Vec<Result<RawRow, FieldError>>) -> (Vec<LibraryMedia>, Vec<RowError>) {
+    let now = Utc::now();
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let result = row
+            .map_err(|e| vec![e])
+            .and_then(|row| validate_row(&row, &now));
+        match result {
+            Ok(item) => items.push(item),
+            Err(field_errors) => errors.push(RowError {
+                row: index + 1,
+                errors: field_errors,
+            }),
+        }
+    }
+
+    (items, errors)
+}
+
+/// Top-level JSON may be an array of items or an object keyed by id
+pub fn parse_json(content: &str) -> Result<Vec<Result<RawRow, FieldError>>, String> {
+    let data: Value =
+        serde_json::from_str(content).map_err(|e| format!("JSON parsing error: {}", e))?;
+
+    let values = match data {
+        Value::Array(items) => items,
+        Value::Object(items) => items.into_iter().map(|(_, item)| item).collect(),
+        _ => {
+            return Err(
+                "JSON parsing error: Invalid JSON structure. Expected an array or object of items."
+                    .into(),
+            )
+        }
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            Value::Object(row) => Ok(row),
+            _ => Err(FieldError {
+                field: "item".into(),
+                message: "must be an object".into(),
+            }),
+        })
+        .collect())
+}
+
+/// Read CSV rows as string maps; blank cells are left out so optional fields stay unset
+pub fn parse_csv(content: &str) -> Result<Vec<Result<RawRow, FieldError>>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::Headers)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("CSV parsing error: {}", e))?
+        .clone();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("CSV parsing error: {}", e))?;
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        if record.len() != headers.len() {
+            rows.push(Err(FieldError {
+                field: "row".into(),
+                message: format!("has {} columns, expected {}", record.len(), headers.len()),
+            }));
+            continue;
+        }
+
+        let row = headers
+            .iter()
+            .zip(record.iter())
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(header, cell)| (header.to_string(), Value::String(cell.to_string())))
+            .collect();
+        rows.push(Ok(row));
+    }
+
+    if rows.is_empty() {
+        return Err("CSV must contain a header and at least one data row.".into());
+    }
+    Ok(rows)
+}
+
+/// Read an import file, refusing anything over `MAX_IMPORT_SIZE` before loading it.
+/// Files without a .json or .csv extension are read too, their format sniffed from the content
+pub fn read_import(path: &Path) -> AppResult<String> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| AppError::from(e).context("Failed to read import file"))?
+        .len();
+    if size > MAX_IMPORT_SIZE {
+        return Err(AppError::invalid_input("File is too large. Maximum size is 10MB."));
+    }
+    std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidData => AppError::invalid_input("Not an import file. Expected a JSON or CSV export."),
+        _ => AppError::from(e).context("Failed to read import file"),
+    })
+}
+
+/// Parse and validate file contents from any supported source
+pub fn import_content(
+    path: &Path,
+    content: &str,
+    source: Option<ImportSource>,
+    format: Option<ImportFormat>,
+    existing_ids: &HashSet<String>,
+) -> Result<ImportResult, String> {
+    if content.trim().is_empty() {
+        return Err("The import file appears to be empty.".into());
+    }
+
+    let source = source
+        .or_else(|| importers::detect_source(path, content))
+        .ok_or("Unrecognized import file. Expected a Watchfolio, Letterboxd, Trakt or IMDb export.")?;
+
+    if source != ImportSource::Watchfolio {
+        return importers::import_external(source, path, content, existing_ids);
+    }
+
+    let format = format
+        .or_else(|| ImportFormat::from_path(path))
+        .unwrap_or_else(|| importers::sniff_format(content));
+    let rows = match format {
+        ImportFormat::Json => parse_json(content)?,
+        ImportFormat::Csv => parse_csv(content)?,
+    };
+    let (items, errors) = validate_rows(rows);
+
+    Ok(ImportResult::new(source, items, errors, existing_ids))
+}
+
+/// Read and validate an import file without going through the webview
+#[tauri::command]
+pub async fn import_data(options: ImportOptions) -> AppResult<ImportResult> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = Path::new(&options.path);
+        let content = read_import(path)?;
+
+        let existing_ids: HashSet<String> = options.existing_ids.into_iter().collect();
+        import_content(path, &content, options.source, options.format, &existing_ids)
+            .map_err(AppError::invalid_input)
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use serde_json::json;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap()
+    }
+
+    fn row(value: Value) -> RawRow {
+        value.as_object().cloned().expect("test rows are objects")
+    }
+
+    /// A valid row with `fields` laid over it; `null` in `fields` overrides, it doesn't remove
+    fn validate(fields: Value) -> Result<LibraryMedia, Vec<FieldError>> {
+        let mut base = row(json!({ "tmdbId": 603, "media_type": "movie", "title": "The Matrix" }));
+        base.extend(row(fields));
+        validate_row(&base, &now())
+    }
+
+    fn validate_without(field: &str) -> Result<LibraryMedia, Vec<FieldError>> {
+        let mut base = row(json!({ "tmdbId": 603, "media_type": "movie", "title": "The Matrix" }));
+        base.remove(field);
+        validate_row(&base, &now())
+    }
+
+    /// The fields of a rejected row, with the first message for each
+    fn errors(result: Result<LibraryMedia, Vec<FieldError>>) -> Vec<(String, String)> {
+        match result {
+            Ok(item) => panic!("expected errors, got {:?}", item),
+            Err(errors) => errors.into_iter().map(|e| (e.field, e.message)).collect(),
+        }
+    }
+
+    fn error_for(fields: Value) -> (String, String) {
+        let mut errors = errors(validate(fields));
+        assert_eq!(errors.len(), 1, "expected one error, got {:?}", errors);
+        errors.remove(0)
+    }
+
+    #[test]
+    fn fills_in_defaults() {
+        let item = validate(json!({})).unwrap();
+        assert_eq!(item.id, "movie-603");
+        assert_eq!(item.status, WatchStatus::None);
+        assert!(!item.is_favorite);
+        assert_eq!(item.user_rating, None);
+        assert_eq!(item.added_at, "2025-01-02T03:04:05.000Z");
+        assert_eq!(item.last_updated_at, "2025-01-02T03:04:05.000Z");
+        assert_eq!(item.release_date, None);
+        assert!(item.genres.is_empty());
+        assert!(item.networks.is_empty());
+        assert_eq!(item.library, None);
+    }
+
+    #[test]
+    fn id_defaults_to_type_and_tmdb_id() {
+        assert_eq!(validate(json!({ "id": "abc" })).unwrap().id, "abc");
+        assert_eq!(validate(json!({ "id": "" })).unwrap().id, "movie-603");
+        assert_eq!(error_for(json!({ "id": 5 })).0, "id");
+        assert_eq!(error_for(json!({ "id": null })).0, "id");
+    }
+
+    #[test]
+    fn tmdb_id_is_coerced_and_must_be_positive() {
+        assert_eq!(validate(json!({ "tmdbId": "27205" })).unwrap().tmdb_id, 27205);
+        assert_eq!(
+            errors(validate_without("tmdbId")),
+            [("tmdbId".to_string(), "must be a number".to_string())]
+        );
+        assert_eq!(error_for(json!({ "tmdbId": "abc" })).1, "must be a number");
+        assert_eq!(error_for(json!({ "tmdbId": 0 })).1, "must be a positive number");
+        assert_eq!(error_for(json!({ "tmdbId": -4 })).1, "must be a positive number");
+        // Like `Number(null)` and `Number("")`, both are 0
+        assert_eq!(error_for(json!({ "tmdbId": null })).1, "must be a positive number");
+        assert_eq!(error_for(json!({ "tmdbId": "" })).1, "must be a positive number");
+        // Stricter than zod: stored as an integer
+        assert_eq!(error_for(json!({ "tmdbId": 1.5 })).1, "must be an integer");
+    }
+
+    #[test]
+    fn media_type_must_be_movie_or_tv() {
+        let item = validate(json!({ "media_type": "tv" })).unwrap();
+        assert_eq!(item.media_type, MediaType::Tv);
+        assert_eq!(item.id, "tv-603");
+        assert_eq!(errors(validate_without("media_type"))[0].0, "media_type");
+        assert_eq!(error_for(json!({ "media_type": "book" })).0, "media_type");
+        assert_eq!(error_for(json!({ "media_type": "Movie" })).0, "media_type");
+    }
+
+    #[test]
+    fn title_is_trimmed_and_optional_but_not_empty_or_null() {
+        assert_eq!(validate(json!({ "title": "  Heat " })).unwrap().title, "Heat");
+        assert_eq!(validate_without("title").unwrap().title, "Untitled movie-603");
+        assert_eq!(error_for(json!({ "title": "   " })).1, "cannot be empty");
+        assert_eq!(error_for(json!({ "title": null })).1, "must be a string");
+        assert_eq!(error_for(json!({ "title": 42 })).1, "must be a string");
+    }
+
+    #[test]
+    fn status_must_be_known() {
+        assert_eq!(validate(json!({ "status": "willWatch" })).unwrap().status, WatchStatus::WillWatch);
+        assert_eq!(validate(json!({ "status": "onHold" })).unwrap().status, WatchStatus::OnHold);
+        assert_eq!(error_for(json!({ "status": "watched" })).0, "status");
+        assert_eq!(error_for(json!({ "status": "Watching" })).0, "status");
+        assert_eq!(error_for(json!({ "status": null })).0, "status");
+    }
+
+    #[test]
+    fn is_favorite_reads_booleans_numbers_and_words() {
+        for value in [json!(true), json!(1), json!("true"), json!("TRUE"), json!("1"), json!("yes")] {
+            assert!(validate(json!({ "isFavorite": value })).unwrap().is_favorite, "{}", value);
+        }
+        for value in [json!(false), json!(0), json!(null), json!(""), json!("false"), json!("0"), json!("no")] {
+            assert!(!validate(json!({ "isFavorite": value })).unwrap().is_favorite, "{}", value);
+        }
+        // Stricter than zod, which would make all of these true
+        for value in [json!("maybe"), json!([]), json!({})] {
+            assert_eq!(error_for(json!({ "isFavorite": value })).1, "must be true or false");
+        }
+    }
+
+    #[test]
+    fn user_rating_is_coerced_and_between_1_and_10() {
+        assert_eq!(validate(json!({ "userRating": "7" })).unwrap().user_rating, Some(7.0));
+        assert_eq!(validate(json!({ "userRating": 10 })).unwrap().user_rating, Some(10.0));
+        assert_eq!(validate(json!({ "userRating": null })).unwrap().user_rating, None);
+        assert_eq!(error_for(json!({ "userRating": 0 })).1, "must be between 1 and 10");
+        assert_eq!(error_for(json!({ "userRating": 10.5 })).1, "must be between 1 and 10");
+        assert_eq!(error_for(json!({ "userRating": "great" })).1, "must be a number");
+    }
+
+    #[test]
+    fn nullable_strings_accept_null_but_not_other_types() {
+        let item = validate(json!({
+            "notes": "Rewatch",
+            "posterPath": "/p.jpg",
+            "overview": null,
+            "userId": "user-1",
+        }))
+        .unwrap();
+        assert_eq!(item.notes.as_deref(), Some("Rewatch"));
+        assert_eq!(item.poster_path.as_deref(), Some("/p.jpg"));
+        assert_eq!(item.overview, None);
+        assert_eq!(item.user_id.as_deref(), Some("user-1"));
+
+        for field in ["notes", "posterPath", "overview", "userId"] {
+            assert_eq!(
+                error_for(json!({ field: 1 })),
+                (field.to_string(), "must be a string".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn dates_accept_what_new_date_does() {
+        let item = validate(json!({
+            "addedAt": "2024-05-06T07:08:09Z",
+            "lastUpdatedAt": 1_700_000_000_000_i64,
+            "releaseDate": "1999-03-31",
+        }))
+        .unwrap();
+        assert_eq!(item.added_at, "2024-05-06T07:08:09.000Z");
+        assert_eq!(item.last_updated_at, "2023-11-14T22:13:20.000Z");
+        assert_eq!(item.release_date.as_deref(), Some("1999-03-31T00:00:00.000Z"));
+
+        // Stricter than zod: `new Date(null)` would be 1970
+        let item = validate(json!({ "addedAt": null, "releaseDate": null })).unwrap();
+        assert_eq!(item.added_at, "2025-01-02T03:04:05.000Z");
+        assert_eq!(item.release_date, None);
+
+        for field in ["addedAt", "lastUpdatedAt", "releaseDate"] {
+            assert_eq!(
+                error_for(json!({ field: "not a date" })),
+                (field.to_string(), "is not a valid date".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn id_lists_take_arrays_or_json_strings() {
+        let item = validate(json!({ "genres": [28, 12], "networks": "[213]" })).unwrap();
+        assert_eq!(item.genres, [28, 12]);
+        assert_eq!(item.networks, [213]);
+
+        assert_eq!(error_for(json!({ "genres": "28,12" })).1, "must be a JSON array of numbers");
+        assert_eq!(error_for(json!({ "genres": ["Action"] })).1, "must be an array of numbers");
+        assert_eq!(error_for(json!({ "networks": null })).1, "must be an array of numbers");
+        // Stricter than zod: ids are stored as integers
+        assert_eq!(error_for(json!({ "genres": [-1] })).1, "must be an array of numbers");
+        assert_eq!(error_for(json!({ "networks": [1.5] })).1, "must be an array of numbers");
+    }
+
+    #[test]
+    fn rating_and_runtime_are_coerced() {
+        let item = validate(json!({ "rating": "8.7", "totalMinutesRuntime": "136" })).unwrap();
+        assert_eq!(item.rating, Some(8.7));
+        assert_eq!(item.total_minutes_runtime, Some(136));
+        assert_eq!(validate(json!({ "rating": null })).unwrap().rating, None);
+        assert_eq!(error_for(json!({ "rating": "high" })).1, "must be a number");
+        assert_eq!(error_for(json!({ "totalMinutesRuntime": 90.5 })).1, "must be an integer");
+    }
+
+    #[test]
+    fn reports_every_bad_field_in_a_row() {
+        let fields: Vec<String> = errors(validate(json!({
+            "tmdbId": "x",
+            "media_type": "book",
+            "userRating": 20,
+        })))
+        .into_iter()
+        .map(|(field, _)| field)
+        .collect();
+        assert_eq!(fields, ["tmdbId", "media_type", "userRating"]);
+    }
+
+    #[test]
+    fn numbers_rows_from_one() {
+        let rows = vec![
+            Ok(row(json!({ "tmdbId": 1, "media_type": "movie" }))),
+            Ok(row(json!({ "tmdbId": 0, "media_type": "movie" }))),
+            Err(FieldError {
+                field: "item".into(),
+                message: "must be an object".into(),
+            }),
+        ];
+        let (items, errors) = validate_rows(rows);
+        assert_eq!(items.len(), 1);
+        assert_eq!(errors.iter().map(|e| e.row).collect::<Vec<_>>(), [2, 3]);
+    }
+
+    #[test]
+    fn parses_json_arrays_and_objects() {
+        let rows = parse_json(r#"[{"tmdbId": 1}, 5]"#).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].is_ok());
+        assert_eq!(rows[1].as_ref().unwrap_err().message, "must be an object");
+
+        let rows = parse_json(r#"{"movie-1": {"tmdbId": 1}, "movie-2": {"tmdbId": 2}}"#).unwrap();
+        assert_eq!(rows.len(), 2);
+
+        assert!(parse_json("42").is_err());
+        assert!(parse_json("[").is_err());
+    }
+
+    #[test]
+    fn parses_csv_leaving_blank_cells_unset() {
+        let rows = parse_csv("tmdbId, media_type ,title,notes\n603,movie,\"The Matrix, Reloaded\",\n\n1,tv\n").unwrap();
+        assert_eq!(rows.len(), 2);
+
+        let first = rows[0].as_ref().unwrap();
+        assert_eq!(first["media_type"], "movie");
+        assert_eq!(first["title"], "The Matrix, Reloaded");
+        assert!(!first.contains_key("notes"));
+        assert_eq!(rows[1].as_ref().unwrap_err().message, "has 2 columns, expected 4");
+
+        assert!(parse_csv("tmdbId,media_type\n").is_err());
+    }
+
+    #[test]
+    fn imports_watchfolio_json_and_csv() {
+        let existing: HashSet<String> = ["movie-603".to_string()].into();
+
+        let json = r#"[{"tmdbId": 603, "media_type": "movie"}, {"tmdbId": 1399, "media_type": "tv", "status": "watching"}]"#;
+        let result = import_content(Path::new("export.json"), json, None, None, &existing).unwrap();
+        assert_eq!(result.source, ImportSource::Watchfolio);
+        assert_eq!((result.total_items, result.movies, result.tv_shows), (2, 1, 1));
+        assert_eq!((result.new_items, result.updated_items), (1, 1));
+
+        let csv = "tmdbId,media_type,isFavorite\n603,movie,false\n1399,tv,true\nabc,tv,\n";
+        let result = import_content(Path::new("export.csv"), csv, None, None, &existing).unwrap();
+        assert_eq!(result.items.len(), 2);
+        assert!(!result.items[0].is_favorite);
+        assert!(result.items[1].is_favorite);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].row, 3);
+
+        // Without an extension the format comes from the content
+        let result = import_content(Path::new("export"), csv, None, None, &existing).unwrap();
+        assert_eq!(result.items.len(), 2);
+
+        assert!(import_content(Path::new("export.csv"), "  \n", None, None, &existing).is_err());
+    }
+
+    #[test]
+    fn read_import_enforces_the_size_limit() {
+        let dir = std::env::temp_dir().join(format!("watchfolio-import-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let small = dir.join("small.json");
+        std::fs::write(&small, "[]").unwrap();
+        assert_eq!(read_import(&small).unwrap(), "[]");
+
+        let large = dir.join("large.json");
+        std::fs::File::create(&large).unwrap().set_len(MAX_IMPORT_SIZE + 1).unwrap();
+        assert_eq!(read_import(&large).unwrap_err().code, ErrorCode::InvalidInput);
+
+        let binary = dir.join("binary.json");
+        std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        assert_eq!(read_import(&binary).unwrap_err().code, ErrorCode::InvalidInput);
+
+        assert_eq!(read_import(&dir.join("missing.json")).unwrap_err().code, ErrorCode::NotFound);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
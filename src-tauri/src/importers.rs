@@ -0,0 +1,741 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::import::{
+    parse_csv, parse_date, to_iso_string, FieldError, ImportFormat, ImportResult, RawRow, RowError,
+};
+use crate::media::{LibraryMedia, MediaType, WatchStatus};
+
+/// Where an import file came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    Watchfolio,
+    Letterboxd,
+    Trakt,
+    Imdb,
+}
+
+/// A title that could not be tied to a TMDB id, kept so it can be matched later
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresolvedItem {
+    pub row: usize,
+    pub title: String,
+    pub year: Option<i32>,
+    pub media_type: Option<MediaType>,
+    pub imdb_id: Option<String>,
+    pub source_url: Option<String>,
+    pub status: WatchStatus,
+    pub user_rating: Option<f64>,
+    pub added_at: Option<String>,
+    pub watch_dates: Vec<String>,
+}
+
+/// One title read from another tracker's export, before it is matched to TMDB
+struct ExternalItem {
+    row: usize,
+    title: String,
+    year: Option<i32>,
+    media_type: Option<MediaType>,
+    tmdb_id: Option<u64>,
+    imdb_id: Option<String>,
+    source_url: Option<String>,
+    status: WatchStatus,
+    user_rating: Option<f64>,
+    added_at: Option<DateTime<Utc>>,
+    watch_dates: Vec<DateTime<Utc>>,
+}
+
+impl ExternalItem {
+    fn new(row: usize, title: String, status: WatchStatus) -> Self {
+        Self {
+            row,
+            title,
+            year: None,
+            media_type: None,
+            tmdb_id: None,
+            imdb_id: None,
+            source_url: None,
+            status,
+            user_rating: None,
+            added_at: None,
+            watch_dates: Vec::new(),
+        }
+    }
+
+    /// Rows describing the same title (rewatches, a rating plus a diary entry) share a key
+    fn key(&self) -> String {
+        match (self.tmdb_id, self.media_type) {
+            (Some(tmdb_id), Some(media_type)) => format!("{}-{}", media_type.as_str(), tmdb_id),
+            _ => match &self.imdb_id {
+                Some(imdb_id) => imdb_id.clone(),
+                None => format!("{}|{:?}", self.title.to_lowercase(), self.year),
+            },
+        }
+    }
+
+    fn merge(&mut self, other: ExternalItem) {
+        if status_rank(other.status) > status_rank(self.status) {
+            self.status = other.status;
+        }
+        self.user_rating = other.user_rating.or(self.user_rating);
+        self.added_at = match (self.added_at, other.added_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.watch_dates.extend(other.watch_dates);
+        self.imdb_id = self.imdb_id.take().or(other.imdb_id);
+        self.source_url = self.source_url.take().or(other.source_url);
+    }
+}
+
+/// When the same title shows up with several statuses, the most "watched" one wins
+fn status_rank(status: WatchStatus) -> u8 {
+    match status {
+        WatchStatus::Completed => 5,
+        WatchStatus::Watching => 4,
+        WatchStatus::OnHold => 3,
+        WatchStatus::Dropped => 2,
+        WatchStatus::WillWatch => 1,
+        WatchStatus::None => 0,
+    }
+}
+
+fn row_error(row: usize, field: &str, message: impl Into<String>) -> RowError {
+    RowError {
+        row,
+        errors: vec![FieldError {
+            field: field.into(),
+            message: message.into(),
+        }],
+    }
+}
+
+/// JSON files start with `[` or `{`; everything else is treated as CSV
+pub fn sniff_format(content: &str) -> ImportFormat {
+    match content.trim_start().chars().next() {
+        Some('[') | Some('{') => ImportFormat::Json,
+        _ => ImportFormat::Csv,
+    }
+}
+
+/// Identify an export by its CSV header or the shape of its first JSON item
+pub fn detect_source(path: &Path, content: &str) -> Option<ImportSource> {
+    let format = ImportFormat::from_path(path).unwrap_or_else(|| sniff_format(content));
+
+    match format {
+        ImportFormat::Csv => {
+            let header = content.trim_start_matches('\u{feff}').lines().next()?;
+            let columns: Vec<String> = header
+                .split(',')
+                .map(|c| c.trim().trim_matches('"').to_string())
+                .collect();
+            let has = |name: &str| columns.iter().any(|c| c == name);
+
+            if has("Letterboxd URI") {
+                Some(ImportSource::Letterboxd)
+            } else if has("Const") && has("Title Type") {
+                Some(ImportSource::Imdb)
+            } else if has("tmdbId") || has("media_type") {
+                Some(ImportSource::Watchfolio)
+            } else {
+                None
+            }
+        }
+        ImportFormat::Json => {
+            let data: Value = serde_json::from_str(content).ok()?;
+            let first = match &data {
+                Value::Array(items) => items.first()?,
+                Value::Object(items) => items.values().next()?,
+                _ => return None,
+            };
+            let first = first.as_object()?;
+
+            if first.contains_key("tmdbId") || first.contains_key("media_type") {
+                Some(ImportSource::Watchfolio)
+            } else if ["movie", "show", "episode"].iter().any(|k| first.contains_key(*k)) {
+                Some(ImportSource::Trakt)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Parse another tracker's export and map it onto library items
+pub fn import_external(
+    source: ImportSource,
+    path: &Path,
+    content: &str,
+    existing_ids: &HashSet<String>,
+) -> Result<ImportResult, String> {
+    let file_name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let (items, errors) = match source {
+        ImportSource::Letterboxd => parse_letterboxd(&file_name, content)?,
+        ImportSource::Imdb => parse_imdb(&file_name, content)?,
+        ImportSource::Trakt => parse_trakt(&file_name, content)?,
+        ImportSource::Watchfolio => {
+            return Err("Watchfolio exports are not handled by the external importers.".into())
+        }
+    };
+
+    Ok(build_result(source, items, errors, existing_ids))
+}
+
+/// Merge duplicate rows and split titles with a TMDB id from those that still need matching
+fn build_result(
+    source: ImportSource,
+    rows: Vec<ExternalItem>,
+    errors: Vec<RowError>,
+    existing_ids: &HashSet<String>,
+) -> ImportResult {
+    let mut order = Vec::new();
+    let mut merged: HashMap<String, ExternalItem> = HashMap::new();
+    for row in rows {
+        let key = row.key();
+        match merged.get_mut(&key) {
+            Some(existing) => existing.merge(row),
+            None => {
+                order.push(key.clone());
+                merged.insert(key, row);
+            }
+        }
+    }
+
+    let now = to_iso_string(&Utc::now());
+    let mut items = Vec::new();
+    let mut unresolved = Vec::new();
+    let mut watch_dates = HashMap::new();
+
+    for key in order {
+        let Some(mut item) = merged.remove(&key) else {
+            continue;
+        };
+        item.watch_dates.sort();
+        item.watch_dates.dedup();
+        let dates: Vec<String> = item.watch_dates.iter().map(to_iso_string).collect();
+        let added_at = item
+            .added_at
+            .or_else(|| item.watch_dates.first().copied())
+            .map(|d| to_iso_string(&d));
+
+        match (item.tmdb_id, item.media_type) {
+            (Some(tmdb_id), Some(media_type)) => {
+                if !dates.is_empty() {
+                    watch_dates.insert(key.clone(), dates);
+                }
+                items.push(LibraryMedia {
+                    id: key,
+                    status: item.status,
+                    is_favorite: false,
+                    user_rating: item.user_rating,
+                    notes: None,
+                    added_at: added_at.unwrap_or_else(|| now.clone()),
+                    last_updated_at: now.clone(),
+                    tmdb_id,
+                    media_type,
+                    title: item.title,
+                    overview: None,
+                    poster_path: None,
+                    release_date: None,
+                    genres: Vec::new(),
+                    rating: None,
+                    total_minutes_runtime: None,
+                    networks: Vec::new(),
+                    library: None,
+                    user_id: None,
+                });
+            }
+            _ => unresolved.push(UnresolvedItem {
+                row: item.row,
+                title: item.title,
+                year: item.year,
+                media_type: item.media_type,
+                imdb_id: item.imdb_id,
+                source_url: item.source_url,
+                status: item.status,
+                user_rating: item.user_rating,
+                added_at,
+                watch_dates: dates,
+            }),
+        }
+    }
+
+    let mut result = ImportResult::new(source, items, errors, existing_ids);
+    result.unresolved = unresolved;
+    result.watch_dates = watch_dates;
+    result
+}
+
+fn text<'a>(row: &'a RawRow, column: &str) -> Option<&'a str> {
+    row.get(column)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn date(row: &RawRow, column: &str) -> Option<DateTime<Utc>> {
+    row.get(column).and_then(parse_date)
+}
+
+fn year(row: &RawRow, column: &str) -> Option<i32> {
+    text(row, column).and_then(|y| y.parse().ok())
+}
+
+/// Letterboxd exports one CSV per list: diary, watched, ratings and watchlist.
+/// None of them carry TMDB ids, so every row ends up unresolved until it is matched.
+fn parse_letterboxd(
+    file_name: &str,
+    content: &str,
+) -> Result<(Vec<ExternalItem>, Vec<RowError>), String> {
+    let rows = parse_csv(content)?;
+    let is_watchlist = file_name.contains("watchlist");
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let row_number = index + 1;
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                errors.push(RowError {
+                    row: row_number,
+                    errors: vec![e],
+                });
+                continue;
+            }
+        };
+
+        let Some(name) = text(&row, "Name") else {
+            errors.push(row_error(row_number, "Name", "cannot be empty"));
+            continue;
+        };
+
+        let status = if is_watchlist {
+            WatchStatus::WillWatch
+        } else {
+            WatchStatus::Completed
+        };
+        let mut item = ExternalItem::new(row_number, name.to_string(), status);
+        item.year = year(&row, "Year");
+        item.media_type = Some(MediaType::Movie);
+        item.source_url = text(&row, "Letterboxd URI").map(str::to_string);
+        item.added_at = date(&row, "Date");
+
+        if let Some(watched) = date(&row, "Watched Date") {
+            item.watch_dates.push(watched);
+        }
+
+        // Letterboxd rates 0.5 to 5 stars in half steps
+        if let Some(rating) = text(&row, "Rating") {
+            match rating.parse::<f64>() {
+                Ok(stars) if (0.5..=5.0).contains(&stars) => item.user_rating = Some(stars * 2.0),
+                _ => {
+                    errors.push(row_error(row_number, "Rating", "must be between 0.5 and 5"));
+                    continue;
+                }
+            }
+        }
+
+        items.push(item);
+    }
+
+    Ok((items, errors))
+}
+
+/// IMDb "Title Type" values for anything that is not a movie
+fn imdb_media_type(title_type: &str) -> Option<MediaType> {
+    let title_type = title_type.to_lowercase().replace(' ', "");
+    if title_type.contains("episode") {
+        None
+    } else if title_type.contains("series") || title_type.contains("miniseries") {
+        Some(MediaType::Tv)
+    } else {
+        Some(MediaType::Movie)
+    }
+}
+
+/// IMDb ratings and watchlist CSVs; titles are keyed by IMDb id until matched to TMDB
+fn parse_imdb(
+    file_name: &str,
+    content: &str,
+) -> Result<(Vec<ExternalItem>, Vec<RowError>), String> {
+    let rows = parse_csv(content)?;
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let row_number = index + 1;
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                errors.push(RowError {
+                    row: row_number,
+                    errors: vec![e],
+                });
+                continue;
+            }
+        };
+
+        let Some(title) = text(&row, "Title").or_else(|| text(&row, "Original Title")) else {
+            errors.push(row_error(row_number, "Title", "cannot be empty"));
+            continue;
+        };
+
+        let title_type = text(&row, "Title Type").unwrap_or("movie");
+        let Some(media_type) = imdb_media_type(title_type) else {
+            errors.push(row_error(
+                row_number,
+                "Title Type",
+                "episodes can't be imported on their own",
+            ));
+            continue;
+        };
+
+        let rating = text(&row, "Your Rating");
+        let is_watchlist = file_name.contains("watchlist") || (rating.is_none() && row.contains_key("Position"));
+        let status = if is_watchlist {
+            WatchStatus::WillWatch
+        } else {
+            WatchStatus::Completed
+        };
+
+        let mut item = ExternalItem::new(row_number, title.to_string(), status);
+        item.media_type = Some(media_type);
+        item.year = year(&row, "Year");
+        item.imdb_id = text(&row, "Const").map(str::to_string);
+        item.source_url = text(&row, "URL").map(str::to_string);
+        item.added_at = date(&row, "Date Rated").or_else(|| date(&row, "Created"));
+
+        if let Some(rating) = rating {
+            match rating.parse::<f64>() {
+                Ok(rating) if (1.0..=10.0).contains(&rating) => item.user_rating = Some(rating),
+                _ => {
+                    errors.push(row_error(row_number, "Your Rating", "must be between 1 and 10"));
+                    continue;
+                }
+            }
+        }
+
+        items.push(item);
+    }
+
+    Ok((items, errors))
+}
+
+/// Trakt history, watchlist and ratings exports. Each entry wraps a `movie`, or a `show`
+/// (optionally with the `episode` that was watched) carrying its TMDB and IMDb ids.
+fn parse_trakt(
+    file_name: &str,
+    content: &str,
+) -> Result<(Vec<ExternalItem>, Vec<RowError>), String> {
+    let data: Value =
+        serde_json::from_str(content).map_err(|e| format!("JSON parsing error: {}", e))?;
+    let Value::Array(entries) = data else {
+        return Err("Invalid Trakt export. Expected an array of entries.".into());
+    };
+    let is_watchlist = file_name.contains("watchlist");
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let row_number = index + 1;
+        let Some(entry) = entry.as_object() else {
+            errors.push(row_error(row_number, "item", "must be an object"));
+            continue;
+        };
+
+        let (media, media_type) = if let Some(movie) = entry.get("movie").and_then(Value::as_object) {
+            (movie, MediaType::Movie)
+        } else if let Some(show) = entry.get("show").and_then(Value::as_object) {
+            (show, MediaType::Tv)
+        } else {
+            errors.push(row_error(row_number, "type", "must contain a movie or a show"));
+            continue;
+        };
+
+        let Some(title) = media.get("title").and_then(Value::as_str) else {
+            errors.push(row_error(row_number, "title", "cannot be empty"));
+            continue;
+        };
+
+        let listed_at = entry.get("listed_at").and_then(parse_date);
+        let watched_at = entry
+            .get("watched_at")
+            .or_else(|| entry.get("last_watched_at"))
+            .and_then(parse_date);
+
+        let status = if is_watchlist || (listed_at.is_some() && watched_at.is_none()) {
+            WatchStatus::WillWatch
+        } else if media_type == MediaType::Tv && entry.contains_key("episode") {
+            WatchStatus::Watching
+        } else {
+            WatchStatus::Completed
+        };
+
+        let ids = media.get("ids").and_then(Value::as_object);
+        let mut item = ExternalItem::new(row_number, title.to_string(), status);
+        item.media_type = Some(media_type);
+        item.year = media
+            .get("year")
+            .and_then(Value::as_i64)
+            .and_then(|y| i32::try_from(y).ok());
+        item.tmdb_id = ids.and_then(|ids| ids.get("tmdb")).and_then(Value::as_u64);
+        item.imdb_id = ids
+            .and_then(|ids| ids.get("imdb"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        item.source_url = ids
+            .and_then(|ids| ids.get("slug"))
+            .and_then(Value::as_str)
+            .map(|slug| {
+                let kind = if media_type == MediaType::Movie { "movies" } else { "shows" };
+                format!("https://trakt.tv/{}/{}", kind, slug)
+            });
+        item.added_at = listed_at.or_else(|| entry.get("rated_at").and_then(parse_date));
+        item.watch_dates.extend(watched_at);
+
+        // Episode and season ratings don't say anything about the show as a whole
+        let rates_title = !entry.contains_key("episode") && !entry.contains_key("season");
+        if let Some(rating) = entry.get("rating").filter(|r| rates_title && !r.is_null()) {
+            match rating.as_f64() {
+                Some(rating) if (1.0..=10.0).contains(&rating) => item.user_rating = Some(rating),
+                _ => {
+                    errors.push(row_error(row_number, "rating", "must be between 1 and 10"));
+                    continue;
+                }
+            }
+        }
+
+        items.push(item);
+    }
+
+    Ok((items, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LETTERBOXD_DIARY: &str = "\
+Date,Name,Year,Letterboxd URI,Rating,Rewatch,Tags,Watched Date
+2024-01-05,The Matrix,1999,https://boxd.it/abc,4.5,,,2024-01-04
+2024-02-10,The Matrix,1999,https://boxd.it/def,,Yes,,2024-02-09
+2024-03-01,Heat,1995,https://boxd.it/ghi,6,,,2024-03-01
+2024-03-02,,1990,https://boxd.it/jkl,,,,
+";
+
+    const IMDB_RATINGS: &str = "\
+Const,Your Rating,Date Rated,Title,Original Title,URL,Title Type,IMDb Rating,Year
+tt0133093,9,2024-01-05,The Matrix,The Matrix,https://www.imdb.com/title/tt0133093/,Movie,8.7,1999
+tt0903747,10,2024-01-06,Breaking Bad,Breaking Bad,https://www.imdb.com/title/tt0903747/,TV Series,9.5,2008
+tt0959621,8,2024-01-07,Pilot,Pilot,https://www.imdb.com/title/tt0959621/,TV Episode,9.0,2008
+tt0000001,11,2024-01-08,Too High,Too High,https://www.imdb.com/title/tt0000001/,Movie,5.0,2000
+";
+
+    fn trakt_history() -> String {
+        let matrix = json!({ "title": "The Matrix", "year": 1999,
+            "ids": { "slug": "the-matrix-1999", "imdb": "tt0133093", "tmdb": 603 } });
+        json!([
+            { "watched_at": "2024-02-05T20:00:00.000Z", "type": "movie", "movie": matrix },
+            { "watched_at": "2024-01-05T20:00:00.000Z", "type": "movie", "movie": matrix },
+            { "watched_at": "2024-01-06T21:00:00.000Z", "type": "episode",
+              "episode": { "season": 1, "number": 1 },
+              "show": { "title": "Breaking Bad", "year": 2008, "ids": { "slug": "breaking-bad", "tmdb": 1396 } } },
+            { "watched_at": "2024-01-07T21:00:00.000Z", "type": "movie",
+              "movie": { "title": "Obscure", "year": 2001, "ids": { "slug": "obscure" } } },
+            { "type": "person", "person": { "name": "Someone" } },
+        ])
+        .to_string()
+    }
+
+    fn import(source: ImportSource, file: &str, content: &str, existing: &[&str]) -> ImportResult {
+        let existing_ids = existing.iter().map(|id| id.to_string()).collect();
+        import_external(source, Path::new(file), content, &existing_ids).unwrap()
+    }
+
+    fn error_rows(result: &ImportResult) -> Vec<(usize, String)> {
+        result
+            .errors
+            .iter()
+            .map(|e| (e.row, e.errors[0].field.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn detects_each_source() {
+        let detect = |file: &str, content: &str| detect_source(Path::new(file), content);
+
+        assert_eq!(detect("diary.csv", LETTERBOXD_DIARY), Some(ImportSource::Letterboxd));
+        assert_eq!(detect("ratings.csv", IMDB_RATINGS), Some(ImportSource::Imdb));
+        assert_eq!(detect("history.json", &trakt_history()), Some(ImportSource::Trakt));
+        assert_eq!(
+            detect("library.csv", "\u{feff}\"id\",\"tmdbId\",\"media_type\"\n"),
+            Some(ImportSource::Watchfolio)
+        );
+        assert_eq!(
+            detect("library.json", r#"[{"tmdbId": 603, "media_type": "movie"}]"#),
+            Some(ImportSource::Watchfolio)
+        );
+        // No extension: the content decides
+        assert_eq!(detect("export", &trakt_history()), Some(ImportSource::Trakt));
+        assert_eq!(detect("other.csv", "Name,Year\nHeat,1995\n"), None);
+        assert_eq!(detect("other.json", r#"[{"name": "Heat"}]"#), None);
+        assert_eq!(detect("empty.json", "[]"), None);
+    }
+
+    #[test]
+    fn letterboxd_rows_merge_and_stay_unresolved() {
+        let result = import(ImportSource::Letterboxd, "diary.csv", LETTERBOXD_DIARY, &[]);
+
+        assert!(result.items.is_empty());
+        assert_eq!(result.unresolved.len(), 1);
+        let matrix = &result.unresolved[0];
+        assert_eq!(matrix.title, "The Matrix");
+        assert_eq!(matrix.year, Some(1999));
+        assert_eq!(matrix.media_type, Some(MediaType::Movie));
+        assert_eq!(matrix.status, WatchStatus::Completed);
+        // Four and a half stars out of five
+        assert_eq!(matrix.user_rating, Some(9.0));
+        assert_eq!(matrix.added_at.as_deref(), Some("2024-01-05T00:00:00.000Z"));
+        assert_eq!(matrix.watch_dates, ["2024-01-04T00:00:00.000Z", "2024-02-09T00:00:00.000Z"]);
+        assert_eq!(matrix.source_url.as_deref(), Some("https://boxd.it/abc"));
+
+        assert_eq!(error_rows(&result), [(3, "Rating".to_string()), (4, "Name".to_string())]);
+    }
+
+    #[test]
+    fn letterboxd_watchlist_is_plan_to_watch() {
+        let content = "Date,Name,Year,Letterboxd URI\n2024-01-01,Dune,2021,https://boxd.it/x\n";
+        let result = import(ImportSource::Letterboxd, "watchlist.csv", content, &[]);
+        assert_eq!(result.unresolved[0].status, WatchStatus::WillWatch);
+        assert_eq!(result.unresolved[0].user_rating, None);
+    }
+
+    #[test]
+    fn imdb_ratings_map_title_types_and_keep_the_scale() {
+        let result = import(ImportSource::Imdb, "ratings.csv", IMDB_RATINGS, &[]);
+
+        assert!(result.items.is_empty());
+        let unresolved: Vec<_> = result
+            .unresolved
+            .iter()
+            .map(|item| (item.imdb_id.as_deref(), item.media_type, item.user_rating, item.status))
+            .collect();
+        assert_eq!(
+            unresolved,
+            [
+                (Some("tt0133093"), Some(MediaType::Movie), Some(9.0), WatchStatus::Completed),
+                (Some("tt0903747"), Some(MediaType::Tv), Some(10.0), WatchStatus::Completed),
+            ]
+        );
+        assert_eq!(result.unresolved[0].added_at.as_deref(), Some("2024-01-05T00:00:00.000Z"));
+        assert_eq!(
+            error_rows(&result),
+            [(3, "Title Type".to_string()), (4, "Your Rating".to_string())]
+        );
+    }
+
+    #[test]
+    fn imdb_watchlist_is_plan_to_watch() {
+        let content = "Position,Const,Created,Title,Title Type,Year\n1,tt1160419,2024-01-01,Dune,Movie,2021\n";
+        let result = import(ImportSource::Imdb, "export.csv", content, &[]);
+        assert_eq!(result.unresolved[0].status, WatchStatus::WillWatch);
+        assert_eq!(result.unresolved[0].added_at.as_deref(), Some("2024-01-01T00:00:00.000Z"));
+    }
+
+    #[test]
+    fn trakt_history_resolves_titles_with_tmdb_ids() {
+        let result = import(ImportSource::Trakt, "history.json", &trakt_history(), &["movie-603"]);
+
+        let items: Vec<_> = result
+            .items
+            .iter()
+            .map(|item| (item.id.as_str(), item.title.as_str(), item.tmdb_id, item.status))
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("movie-603", "The Matrix", 603, WatchStatus::Completed),
+                ("tv-1396", "Breaking Bad", 1396, WatchStatus::Watching),
+            ]
+        );
+        // Rewatches merge into one item, first watch first
+        assert_eq!(result.items[0].added_at, "2024-01-05T20:00:00.000Z");
+        assert_eq!(
+            result.watch_dates["movie-603"],
+            ["2024-01-05T20:00:00.000Z", "2024-02-05T20:00:00.000Z"]
+        );
+
+        assert_eq!(result.unresolved.len(), 1);
+        assert_eq!(result.unresolved[0].title, "Obscure");
+        assert_eq!(
+            result.unresolved[0].source_url.as_deref(),
+            Some("https://trakt.tv/movies/obscure")
+        );
+        assert_eq!(error_rows(&result), [(5, "type".to_string())]);
+
+        assert_eq!((result.total_items, result.movies, result.tv_shows), (2, 1, 1));
+        assert_eq!((result.new_items, result.updated_items), (1, 1));
+    }
+
+    #[test]
+    fn trakt_ratings_and_watchlist() {
+        let ratings = json!([
+            { "rated_at": "2024-01-05T20:00:00.000Z", "rating": 8, "type": "movie",
+              "movie": { "title": "Heat", "ids": { "tmdb": 949 } } },
+            { "rated_at": "2024-01-05T20:00:00.000Z", "rating": 10, "type": "episode",
+              "episode": { "season": 1, "number": 1 },
+              "show": { "title": "Breaking Bad", "ids": { "tmdb": 1396 } } },
+            { "rating": 12, "movie": { "title": "Too High", "ids": { "tmdb": 1 } } },
+        ]);
+        let result = import(ImportSource::Trakt, "ratings.json", &ratings.to_string(), &[]);
+        assert_eq!(result.items[0].user_rating, Some(8.0));
+        assert_eq!(result.items[0].added_at, "2024-01-05T20:00:00.000Z");
+        // An episode rating says nothing about the show
+        assert_eq!(result.items[1].user_rating, None);
+        assert_eq!(error_rows(&result), [(3, "rating".to_string())]);
+
+        let watchlist = json!([
+            { "listed_at": "2024-01-01T00:00:00.000Z", "movie": { "title": "Dune", "ids": { "tmdb": 438631 } } },
+        ]);
+        let result = import(ImportSource::Trakt, "watchlist.json", &watchlist.to_string(), &[]);
+        assert_eq!(result.items[0].status, WatchStatus::WillWatch);
+    }
+
+    #[test]
+    fn trakt_rejects_anything_but_an_array() {
+        let existing = HashSet::new();
+        assert!(import_external(ImportSource::Trakt, Path::new("history.json"), "{}", &existing).is_err());
+        assert!(import_external(ImportSource::Trakt, Path::new("history.json"), "[", &existing).is_err());
+        assert!(import_external(ImportSource::Watchfolio, Path::new("library.json"), "[]", &existing).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_the_most_watched_status_and_earliest_date() {
+        let date = |day: u32| parse_date(&json!(format!("2024-01-{:02}", day)));
+        let mut first = ExternalItem::new(1, "Heat".into(), WatchStatus::WillWatch);
+        first.added_at = date(10);
+        first.user_rating = Some(6.0);
+        let mut second = ExternalItem::new(2, "Heat".into(), WatchStatus::Completed);
+        second.added_at = date(3);
+        second.user_rating = Some(8.0);
+        second.imdb_id = Some("tt0113277".into());
+
+        first.merge(second);
+        assert_eq!(first.status, WatchStatus::Completed);
+        assert_eq!(first.added_at, date(3));
+        assert_eq!(first.user_rating, Some(8.0));
+        assert_eq!(first.imdb_id.as_deref(), Some("tt0113277"));
+        assert_eq!(first.row, 1);
+    }
+}
@@ -1,37 +1,43 @@
-use serde::{Deserialize, Serialize};
 use tauri::Manager;
 
+mod error;
+mod export;
+mod file_drop;
+mod import;
+mod importers;
+mod library;
+mod logging;
+mod media;
 mod menu;
 mod tray;
 mod shortcuts;
 mod system_settings;
 
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod autostart;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod crash;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod deep_link;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod diagnostics;
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 mod updater;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod window_state;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExportOptions {
-  pub path: String,
-  pub format: String,
-  pub data: String,
-}
-
-/// Export data to file system
-#[tauri::command]
-async fn export_data(options: ExportOptions) -> Result<String, String> {
-  std::fs::write(&options.path, options.data)
-    .map_err(|e| format!("Failed to export data: {}", e))?;
-  Ok(options.path)
+fn platform_info() -> serde_json::Value {
+  serde_json::json!({
+    "os": std::env::consts::OS,
+    "arch": std::env::consts::ARCH,
+    "family": std::env::consts::FAMILY,
+  })
 }
 
 /// Get platform information
 #[tauri::command]
-fn get_platform_info() -> Result<serde_json::Value, String> {
-  Ok(serde_json::json!({
-    "os": std::env::consts::OS,
-    "arch": std::env::consts::ARCH,
-    "family": std::env::consts::FAMILY,
-  }))
+fn get_platform_info() -> error::AppResult<serde_json::Value> {
+  Ok(platform_info())
 }
 
 /// Check if running in Tauri
@@ -40,32 +46,100 @@ fn is_tauri() -> bool {
   true
 }
 
-/// Check if app was autostarted
-#[tauri::command]
-fn was_autostarted() -> bool {
-  let args: Vec<String> = std::env::args().collect();
-  args.contains(&"--autostarted".to_string())
+/// Arguments of a second launch, forwarded to the running instance
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[derive(Clone, serde::Serialize)]
+struct SecondInstance {
+  argv: Vec<String>,
+  cwd: String,
+}
+
+/// A second launch exits right away; bring the running instance forward and hand it the launch arguments.
+/// Deep link URLs in `argv` reach the deep-link plugin through the single-instance `deep-link` feature.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn on_second_instance(app: &tauri::AppHandle, argv: Vec<String>, cwd: String) {
+  use tauri::Emitter;
+
+  log::info!("Second instance launched with {:?}", argv);
+
+  if let Some(window) = app.get_webview_window("main") {
+    // Autostart entries racing a manual launch shouldn't pop the window up
+    if !argv.iter().any(|arg| arg == autostart::AUTOSTART_ARG) {
+      let _ = window.show();
+      let _ = window.unminimize();
+      let _ = window.set_focus();
+    }
+    let _ = window.emit("app:second-instance", SecondInstance { argv, cwd });
+  }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  let mut builder = tauri::Builder::default()
-    .plugin(tauri_plugin_shell::init());
+  let mut builder = tauri::Builder::default();
+
+  // Must be the first plugin so a duplicate process exits before anything else is set up
+  #[cfg(not(any(target_os = "android", target_os = "ios")))]
+  {
+    builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+      on_second_instance(app, argv, cwd);
+    }));
+  }
+
+  builder = builder.plugin(tauri_plugin_shell::init());
 
   #[cfg(not(any(target_os = "android", target_os = "ios")))]
   {
     builder = builder.invoke_handler(tauri::generate_handler![
-      export_data,
+      export::export_data,
+      export::export_begin,
+      export::export_chunk,
+      export::export_finish,
+      export::export_cancel,
+      import::import_data,
+      library::library_get,
+      library::library_list,
+      library::library_upsert,
+      library::library_delete,
+      library::library_clear,
+      library::set_current_media,
+      file_drop::take_dropped_import,
+      file_drop::discard_dropped_import,
       get_platform_info,
       is_tauri,
-      was_autostarted,
+      logging::read_logs,
+      logging::get_log_level,
+      logging::set_log_level,
+      autostart::was_autostarted,
+      autostart::enable_autostart,
+      autostart::disable_autostart,
+      autostart::is_autostart_enabled,
+      autostart::autostart_diagnostics,
+      crash::get_crash_reports,
+      crash::dismiss_crash_report,
+      deep_link::deep_links_ready,
+      diagnostics::export_diagnostics,
+      diagnostics::issue_report_url,
+      shortcuts::list_shortcuts,
+      shortcuts::rebind_shortcut,
+      shortcuts::reset_shortcuts,
       shortcuts::register_custom_shortcut,
       shortcuts::unregister_shortcut,
       shortcuts::is_shortcut_registered,
       tray::update_tray_tooltip,
+      tray::set_sync_state,
       tray::show_tray_notification,
       updater::manual_check_updates,
-      updater::install_update,
+      updater::download_update,
+      updater::cancel_update_download,
+      updater::restart_to_update,
+      updater::get_update_channel,
+      updater::set_update_channel,
+      updater::get_updater_settings,
+      updater::skip_update_version,
+      updater::remind_update_later,
+      updater::set_update_schedule,
+      system_settings::get_system_settings,
+      system_settings::update_system_settings,
       system_settings::set_keep_running_in_background,
       system_settings::get_keep_running_in_background,
     ]);
@@ -74,9 +148,22 @@ pub fn run() {
   #[cfg(any(target_os = "android", target_os = "ios"))]
   {
     builder = builder.invoke_handler(tauri::generate_handler![
-      export_data,
+      export::export_data,
+      export::export_begin,
+      export::export_chunk,
+      export::export_finish,
+      export::export_cancel,
+      import::import_data,
+      library::library_get,
+      library::library_list,
+      library::library_upsert,
+      library::library_delete,
+      library::library_clear,
       get_platform_info,
       is_tauri,
+      logging::read_logs,
+      logging::get_log_level,
+      logging::set_log_level,
     ]);
   }
 
@@ -87,32 +174,46 @@ pub fn run() {
       .plugin(tauri_plugin_dialog::init())
       .plugin(tauri_plugin_notification::init())
       .plugin(tauri_plugin_deep_link::init())
-      .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+      .plugin(
+        tauri_plugin_global_shortcut::Builder::new()
+          .with_handler(shortcuts::handle_shortcut)
+          .build(),
+      )
       .plugin(tauri_plugin_updater::Builder::new().build())
       .plugin(tauri_plugin_process::init())
       .plugin(tauri_plugin_opener::init())
       .plugin(tauri_plugin_autostart::init(
         tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-        Some(vec!["--autostarted"]),
-      ));
+        Some(vec![autostart::AUTOSTART_ARG]),
+      ))
+      .on_window_event(window_state::on_window_event);
   }
 
   builder
     .setup(|app| {
-      if cfg!(debug_assertions) {
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .build(),
-        )?;
-      }
+      app.handle().plugin(logging::plugin())?;
+      logging::init(app.handle());
+      #[cfg(not(any(target_os = "android", target_os = "ios")))]
+      crash::install_hook(app.handle());
+
+      app.manage(export::ExportSessions::default());
+      app.manage(library::init(app.handle()));
+      app.manage(library::CurrentMedia::default());
 
       #[cfg(not(any(target_os = "android", target_os = "ios")))]
       {
-        // Initialize system settings state
-        app.manage(system_settings::SystemSettings {
-          keep_running_in_background: std::sync::Mutex::new(true),
-        });
+        // Load persisted system settings before anything consults them
+        app.manage(system_settings::SystemSettingsStore::load(app.handle()));
+        app.manage(updater::UpdaterStore::load(app.handle()));
+        app.manage(updater::UpdateStaging::default());
+        updater::prune_staged(app.handle());
+        app.manage(file_drop::DroppedImports::default());
+        crash::redact_pending(app.handle());
+        // Register global shortcuts first so the menu can show their chords
+        if let Err(e) = shortcuts::register_shortcuts(&app.handle()) {
+          log::warn!("Failed to register some shortcuts: {}", e);
+        }
+
         // Create native menu
         let menu = menu::create_menu(&app.handle())?;
         app.set_menu(menu)?;
@@ -121,43 +222,62 @@ pub fn run() {
         // Create system tray
         tray::create_tray(&app.handle())?;
 
-        // Register global shortcuts
-        if let Err(e) = shortcuts::register_shortcuts(&app.handle()) {
-          log::warn!("Failed to register some shortcuts: {}", e);
-        }
 
-        // Start background updater (checks every 24 hours)
+        // Start background updater (checks when overdue, then on the configured interval)
         updater::start_background_updater(app.handle().clone());
 
-        // Handle --autostarted flag for startup behavior
-        // Hide window on autostart - frontend will show it if startMinimized is false
-        let args: Vec<String> = std::env::args().collect();
-        if args.contains(&"--autostarted".to_string()) {
-          if let Some(window) = app.get_webview_window("main") {
-            let _ = window.hide();
-          }
-        }
+        // Route watchfolio:// links, including the one the app was launched with
+        deep_link::init(app.handle());
+
+        // Windows start hidden; put them back where they were before any is shown
+        app.manage(window_state::WindowStateStore::load(app.handle()));
+        window_state::restore(app.handle());
 
-        // Handle window close event based on keep_running_in_background setting
+        // The main window starts hidden; show it unless autostarted with start minimized
+        autostart::sync_launch_on_startup(app.handle());
+        autostart::apply_startup_visibility(app.handle());
+
+        // Handle window close and minimize based on system settings,
+        // and files dropped onto the window for import
         if let Some(window) = app.get_webview_window("main") {
           let app_handle = app.handle().clone();
-          window.on_window_event(move |event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+          window.on_window_event(move |event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+              if system_settings::should_keep_running(&app_handle) {
+                api.prevent_close();
+                if let Some(window) = app_handle.get_webview_window("main") {
+                  let _ = window.hide();
+                }
+              } else {
+                // Hidden windows like quick-add would otherwise keep the process alive
+                app_handle.exit(0);
+              }
+            }
+            tauri::WindowEvent::Resized(_) => {
               if let Some(window) = app_handle.get_webview_window("main") {
-                let should_keep_running = system_settings::should_keep_running(&app_handle);
-
-                if should_keep_running {
-                  api.prevent_close();
+                if window.is_minimized().unwrap_or(false) && system_settings::should_minimize_to_tray(&app_handle) {
                   let _ = window.hide();
                 }
               }
             }
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+              file_drop::handle_drop(&app_handle, paths.clone());
+            }
+            _ => {}
           });
         }
       }
 
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|_app, _event| {
+      #[cfg(not(any(target_os = "android", target_os = "ios")))]
+      if let tauri::RunEvent::Exit = _event {
+        updater::stop_background_updater(_app);
+        window_state::save(_app);
+        updater::install_staged_on_exit(_app);
+      }
+    });
 }
@@ -0,0 +1,616 @@
+use chrono::Utc;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::{AppError, AppResult};
+use crate::import::to_iso_string;
+use crate::media::{LibraryMedia, MediaType, WatchStatus};
+
+/// Bump when the schema changes and add a step to `migrate`
+const SCHEMA_VERSION: i32 = 1;
+
+const COLUMNS: &str = "id, status, is_favorite, user_rating, notes, added_at, last_updated_at, \
+    tmdb_id, media_type, title, overview, poster_path, release_date, genres, rating, \
+    total_minutes_runtime, networks, library, user_id";
+
+/// The user's library in SQLite, kept in step with RxDB by the main window, so native features can
+/// read and change it and it outlives a wiped webview cache
+pub struct LibraryStore {
+    conn: Mutex<Connection>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LibrarySort {
+    AddedAt,
+    #[default]
+    LastUpdatedAt,
+    Title,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryQuery {
+    pub status: Option<WatchStatus>,
+    pub media_type: Option<MediaType>,
+    pub is_favorite: Option<bool>,
+    #[serde(default)]
+    pub sort: LibrarySort,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LibraryChangeKind {
+    Upserted,
+    Deleted,
+    Cleared,
+}
+
+/// Identifies a title whether or not it is in the library yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaRef {
+    pub tmdb_id: u64,
+    #[serde(rename = "media_type")]
+    pub media_type: MediaType,
+    pub title: String,
+}
+
+impl From<&LibraryMedia> for MediaRef {
+    fn from(media: &LibraryMedia) -> Self {
+        Self {
+            tmdb_id: media.tmdb_id,
+            media_type: media.media_type,
+            title: media.title.clone(),
+        }
+    }
+}
+
+/// The title open in the main window, used by native quick actions; only `set_current_media` changes it
+#[derive(Default)]
+pub struct CurrentMedia(Mutex<Option<MediaRef>>);
+
+impl CurrentMedia {
+    pub fn get(&self) -> Option<MediaRef> {
+        self.0.lock().ok().and_then(|current| current.clone())
+    }
+
+    fn set(&self, media: Option<MediaRef>) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = media;
+        }
+    }
+}
+
+/// Payload of the `library:changed` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryChange {
+    pub kind: LibraryChangeKind,
+    pub ids: Vec<String>,
+}
+
+impl LibraryStore {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::from_connection(conn)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> rusqlite::Result<Self> {
+        migrate(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> AppResult<std::sync::MutexGuard<'_, Connection>> {
+        Ok(self.conn.lock()?)
+    }
+
+    pub fn get(&self, id: &str) -> AppResult<Option<LibraryMedia>> {
+        let conn = self.conn()?;
+        conn.query_row(
+            &format!("SELECT {} FROM library_media WHERE id = ?1", COLUMNS),
+            params![id],
+            row_to_media,
+        )
+        .optional()
+        .map_err(AppError::from)
+    }
+
+    pub fn list(&self, query: &LibraryQuery) -> AppResult<Vec<LibraryMedia>> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(status) = query.status {
+            conditions.push("status = ?");
+            values.push(SqlValue::Text(status.as_str().into()));
+        }
+        if let Some(media_type) = query.media_type {
+            conditions.push("media_type = ?");
+            values.push(SqlValue::Text(media_type.as_str().into()));
+        }
+        if let Some(is_favorite) = query.is_favorite {
+            conditions.push("is_favorite = ?");
+            values.push(SqlValue::Integer(is_favorite as i64));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let order_by = match query.sort {
+            LibrarySort::AddedAt => "added_at DESC",
+            LibrarySort::LastUpdatedAt => "last_updated_at DESC",
+            LibrarySort::Title => "title COLLATE NOCASE ASC",
+        };
+        values.push(SqlValue::Integer(query.limit.map_or(-1, i64::from)));
+        values.push(SqlValue::Integer(query.offset.map_or(0, i64::from)));
+
+        let sql = format!(
+            "SELECT {} FROM library_media {} ORDER BY {} LIMIT ? OFFSET ?",
+            COLUMNS, where_clause, order_by
+        );
+
+        let conn = self.conn()?;
+        let mut statement = conn.prepare(&sql)?;
+        let rows = statement.query_map(params_from_iter(values), row_to_media)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    pub fn find(&self, media_type: MediaType, tmdb_id: u64) -> AppResult<Option<LibraryMedia>> {
+        let conn = self.conn()?;
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM library_media WHERE media_type = ?1 AND tmdb_id = ?2",
+                COLUMNS
+            ),
+            params![media_type.as_str(), tmdb_id as i64],
+            row_to_media,
+        )
+        .optional()
+        .map_err(AppError::from)
+    }
+
+    /// Change a title's status, adding it to the library first if needed
+    pub fn set_status(&self, media: &MediaRef, status: WatchStatus) -> AppResult<LibraryMedia> {
+        let now = to_iso_string(&Utc::now());
+        let item = match self.find(media.media_type, media.tmdb_id)? {
+            Some(mut item) => {
+                item.status = status;
+                item.last_updated_at = now;
+                item
+            }
+            None => LibraryMedia {
+                id: format!("{}-{}", media.media_type.as_str(), media.tmdb_id),
+                status,
+                is_favorite: false,
+                user_rating: None,
+                notes: None,
+                added_at: now.clone(),
+                last_updated_at: now,
+                tmdb_id: media.tmdb_id,
+                media_type: media.media_type,
+                title: media.title.clone(),
+                overview: None,
+                poster_path: None,
+                release_date: None,
+                genres: Vec::new(),
+                rating: None,
+                total_minutes_runtime: None,
+                networks: Vec::new(),
+                library: None,
+                user_id: None,
+            },
+        };
+
+        self.upsert(std::slice::from_ref(&item))?;
+        Ok(item)
+    }
+
+    /// Rate a library item, or clear its rating with `None`
+    pub fn set_rating(&self, id: &str, rating: Option<f64>) -> AppResult<LibraryMedia> {
+        let mut item = self
+            .get(id)?
+            .ok_or_else(|| AppError::not_found(format!("Item not in library: {}", id)))?;
+        item.user_rating = rating;
+        item.last_updated_at = to_iso_string(&Utc::now());
+
+        self.upsert(std::slice::from_ref(&item))?;
+        Ok(item)
+    }
+
+    /// `id` -> `lastUpdatedAt` for every item, for cheap comparisons against incoming data
+    pub fn index(&self) -> AppResult<HashMap<String, String>> {
+        let conn = self.conn()?;
+        let mut statement = conn.prepare("SELECT id, last_updated_at FROM library_media")?;
+        let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<rusqlite::Result<HashMap<_, _>>>()?)
+    }
+
+    pub fn upsert(&self, items: &[LibraryMedia]) -> AppResult<()> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        {
+            let mut statement = tx
+                .prepare(&format!(
+                    "INSERT OR REPLACE INTO library_media ({}) VALUES \
+                     (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+                    COLUMNS
+                ))?;
+
+            for item in items {
+                statement
+                    .execute(params![
+                        item.id,
+                        item.status.as_str(),
+                        item.is_favorite,
+                        item.user_rating,
+                        item.notes,
+                        item.added_at,
+                        item.last_updated_at,
+                        item.tmdb_id as i64,
+                        item.media_type.as_str(),
+                        item.title,
+                        item.overview,
+                        item.poster_path,
+                        item.release_date,
+                        serde_json::to_string(&item.genres).unwrap_or_else(|_| "[]".into()),
+                        item.rating,
+                        item.total_minutes_runtime,
+                        serde_json::to_string(&item.networks).unwrap_or_else(|_| "[]".into()),
+                        item.library,
+                        item.user_id,
+                    ])?;
+            }
+        }
+        Ok(tx.commit()?)
+    }
+
+    pub fn delete(&self, ids: &[String]) -> AppResult<usize> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let mut deleted = 0;
+        for id in ids {
+            deleted += tx
+                .execute("DELETE FROM library_media WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    pub fn clear(&self) -> AppResult<()> {
+        self.conn()?
+            .execute("DELETE FROM library_media", [])
+            .map(|_| ())
+            .map_err(AppError::from)
+    }
+}
+
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    if version < 1 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS library_media (
+                id TEXT PRIMARY KEY NOT NULL,
+                status TEXT NOT NULL DEFAULT 'none',
+                is_favorite INTEGER NOT NULL DEFAULT 0,
+                user_rating REAL,
+                notes TEXT,
+                added_at TEXT NOT NULL,
+                last_updated_at TEXT NOT NULL,
+                tmdb_id INTEGER NOT NULL,
+                media_type TEXT NOT NULL CHECK (media_type IN ('movie', 'tv')),
+                title TEXT NOT NULL,
+                overview TEXT,
+                poster_path TEXT,
+                release_date TEXT,
+                genres TEXT NOT NULL DEFAULT '[]',
+                rating REAL,
+                total_minutes_runtime INTEGER,
+                networks TEXT NOT NULL DEFAULT '[]',
+                library TEXT,
+                user_id TEXT
+            );
+            CREATE INDEX IF NOT EXISTS library_media_status ON library_media (status, last_updated_at);
+            CREATE INDEX IF NOT EXISTS library_media_added_at ON library_media (added_at);
+            CREATE INDEX IF NOT EXISTS library_media_tmdb ON library_media (media_type, tmdb_id);",
+        )?;
+    }
+
+    if version < SCHEMA_VERSION {
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+    Ok(())
+}
+
+fn row_to_media(row: &Row) -> rusqlite::Result<LibraryMedia> {
+    let status: String = row.get("status")?;
+    let media_type: String = row.get("media_type")?;
+    let genres: String = row.get("genres")?;
+    let networks: String = row.get("networks")?;
+    let tmdb_id: i64 = row.get("tmdb_id")?;
+
+    Ok(LibraryMedia {
+        id: row.get("id")?,
+        status: WatchStatus::parse(&status).unwrap_or_default(),
+        is_favorite: row.get("is_favorite")?,
+        user_rating: row.get("user_rating")?,
+        notes: row.get("notes")?,
+        added_at: row.get("added_at")?,
+        last_updated_at: row.get("last_updated_at")?,
+        tmdb_id: tmdb_id as u64,
+        // Guarded by the CHECK constraint on the column
+        media_type: MediaType::parse(&media_type).unwrap_or(MediaType::Movie),
+        title: row.get("title")?,
+        overview: row.get("overview")?,
+        poster_path: row.get("poster_path")?,
+        release_date: row.get("release_date")?,
+        genres: serde_json::from_str(&genres).unwrap_or_default(),
+        rating: row.get("rating")?,
+        total_minutes_runtime: row.get("total_minutes_runtime")?,
+        networks: serde_json::from_str(&networks).unwrap_or_default(),
+        library: row.get("library")?,
+        user_id: row.get("user_id")?,
+    })
+}
+
+/// Open `library.db` in the app data dir, falling back to memory so the app still starts
+pub fn init(app: &AppHandle) -> LibraryStore {
+    let opened = app
+        .path()
+        .app_data_dir()
+        .map_err(AppError::from)
+        .and_then(|dir| {
+            std::fs::create_dir_all(&dir)?;
+            Ok(LibraryStore::open(&dir.join("library.db"))?)
+        });
+
+    match opened {
+        Ok(store) => store,
+        Err(e) => {
+            log::error!("Failed to open library database, using an in-memory store: {}", e);
+            LibraryStore::open_in_memory().expect("in-memory SQLite is always available")
+        }
+    }
+}
+
+/// Broadcast a change to every window; the main window copies native changes into RxDB
+pub fn notify_change(app: &AppHandle, kind: LibraryChangeKind, ids: Vec<String>) {
+    let _ = app.emit("library:changed", LibraryChange { kind, ids });
+}
+
+/// Run `f` against the store on the blocking pool, so SQLite never holds up the main thread
+async fn with_store<T: Send + 'static>(
+    app: &AppHandle,
+    f: impl FnOnce(&LibraryStore) -> AppResult<T> + Send + 'static,
+) -> AppResult<T> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || f(&app.state::<LibraryStore>())).await?
+}
+
+#[tauri::command]
+pub async fn library_get(app: AppHandle, id: String) -> AppResult<Option<LibraryMedia>> {
+    with_store(&app, move |store| store.get(&id)).await
+}
+
+#[tauri::command]
+pub async fn library_list(app: AppHandle, query: Option<LibraryQuery>) -> AppResult<Vec<LibraryMedia>> {
+    with_store(&app, move |store| store.list(&query.unwrap_or_default())).await
+}
+
+/// Set the title quick actions apply to; `None` when nothing is open
+#[tauri::command]
+pub fn set_current_media(
+    app: AppHandle,
+    current: State<CurrentMedia>,
+    media: Option<MediaRef>,
+) -> AppResult<()> {
+    current.set(media.clone());
+    let _ = app.emit("library:current-changed", media);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn library_upsert(app: AppHandle, items: Vec<LibraryMedia>) -> AppResult<()> {
+    let ids = items.iter().map(|item| item.id.clone()).collect();
+    with_store(&app, move |store| store.upsert(&items)).await?;
+    notify_change(&app, LibraryChangeKind::Upserted, ids);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn library_delete(app: AppHandle, ids: Vec<String>) -> AppResult<usize> {
+    let to_delete = ids.clone();
+    let deleted = with_store(&app, move |store| store.delete(&to_delete)).await?;
+    notify_change(&app, LibraryChangeKind::Deleted, ids);
+    Ok(deleted)
+}
+
+#[tauri::command]
+pub async fn library_clear(app: AppHandle) -> AppResult<()> {
+    with_store(&app, |store| store.clear()).await?;
+    // Whatever was open is gone with the library
+    app.state::<CurrentMedia>().set(None);
+    let _ = app.emit("library:current-changed", None::<MediaRef>);
+    notify_change(&app, LibraryChangeKind::Cleared, Vec::new());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, tmdb_id: u64, status: WatchStatus, title: &str, added_at: &str) -> LibraryMedia {
+        LibraryMedia {
+            id: id.to_string(),
+            status,
+            is_favorite: false,
+            user_rating: None,
+            notes: None,
+            added_at: added_at.to_string(),
+            last_updated_at: added_at.to_string(),
+            tmdb_id,
+            media_type: MediaType::Movie,
+            title: title.to_string(),
+            overview: None,
+            poster_path: None,
+            release_date: None,
+            genres: vec![28, 878],
+            rating: Some(8.2),
+            total_minutes_runtime: Some(136),
+            networks: Vec::new(),
+            library: None,
+            user_id: None,
+        }
+    }
+
+    fn store_with_items() -> LibraryStore {
+        let store = LibraryStore::open_in_memory().unwrap();
+        store
+            .upsert(&[
+                item("a", 603, WatchStatus::Completed, "The Matrix", "2025-01-01T00:00:00.000Z"),
+                item("b", 604, WatchStatus::Watching, "the Matrix Reloaded", "2025-01-03T00:00:00.000Z"),
+                item("c", 605, WatchStatus::Watching, "Animatrix", "2025-01-02T00:00:00.000Z"),
+            ])
+            .unwrap();
+        store
+    }
+
+    fn ids(items: Vec<LibraryMedia>) -> Vec<String> {
+        items.into_iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn upsert_round_trips_every_field() {
+        let store = LibraryStore::open_in_memory().unwrap();
+        let mut original = item("a", 603, WatchStatus::OnHold, "The Matrix", "2025-01-01T00:00:00.000Z");
+        original.is_favorite = true;
+        original.user_rating = Some(9.0);
+        original.notes = Some("Rewatch".into());
+        original.networks = vec![49];
+        store.upsert(std::slice::from_ref(&original)).unwrap();
+
+        assert_eq!(store.get("a").unwrap(), Some(original));
+        assert_eq!(store.get("missing").unwrap(), None);
+    }
+
+    #[test]
+    fn upsert_replaces_an_existing_item() {
+        let store = store_with_items();
+        let mut changed = store.get("a").unwrap().unwrap();
+        changed.status = WatchStatus::Dropped;
+        store.upsert(&[changed]).unwrap();
+
+        assert_eq!(store.get("a").unwrap().unwrap().status, WatchStatus::Dropped);
+        assert_eq!(store.list(&LibraryQuery::default()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn delete_counts_only_existing_items() {
+        let store = store_with_items();
+        assert_eq!(store.delete(&["a".into(), "missing".into()]).unwrap(), 1);
+        assert_eq!(store.get("a").unwrap(), None);
+
+        store.clear().unwrap();
+        assert!(store.list(&LibraryQuery::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_filters_sorts_and_pages() {
+        let store = store_with_items();
+        let query = |query: LibraryQuery| ids(store.list(&query).unwrap());
+
+        assert_eq!(
+            query(LibraryQuery {
+                status: Some(WatchStatus::Watching),
+                sort: LibrarySort::AddedAt,
+                ..Default::default()
+            }),
+            ["b", "c"]
+        );
+        assert_eq!(
+            query(LibraryQuery {
+                sort: LibrarySort::Title,
+                ..Default::default()
+            }),
+            ["c", "a", "b"]
+        );
+        assert_eq!(
+            query(LibraryQuery {
+                sort: LibrarySort::AddedAt,
+                limit: Some(1),
+                offset: Some(1),
+                ..Default::default()
+            }),
+            ["c"]
+        );
+        assert!(query(LibraryQuery {
+            is_favorite: Some(true),
+            ..Default::default()
+        })
+        .is_empty());
+        assert!(query(LibraryQuery {
+            media_type: Some(MediaType::Tv),
+            ..Default::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn find_and_index_see_stored_items() {
+        let store = store_with_items();
+        assert_eq!(store.find(MediaType::Movie, 604).unwrap().unwrap().id, "b");
+        assert_eq!(store.find(MediaType::Tv, 604).unwrap(), None);
+
+        let index = store.index().unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index["a"], "2025-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn set_status_updates_or_adds_the_title() {
+        let store = store_with_items();
+        let matrix = store.get("a").unwrap().unwrap();
+        let existing = store.set_status(&MediaRef::from(&matrix), WatchStatus::Watching).unwrap();
+        assert_eq!(existing.id, "a");
+        assert_eq!(existing.status, WatchStatus::Watching);
+        assert_ne!(existing.last_updated_at, "2025-01-01T00:00:00.000Z");
+
+        let added = store
+            .set_status(
+                &MediaRef {
+                    tmdb_id: 1396,
+                    media_type: MediaType::Tv,
+                    title: "Breaking Bad".into(),
+                },
+                WatchStatus::WillWatch,
+            )
+            .unwrap();
+        assert_eq!(added.id, "tv-1396");
+        assert_eq!(store.get("tv-1396").unwrap(), Some(added));
+    }
+
+    #[test]
+    fn set_rating_sets_and_clears() {
+        let store = store_with_items();
+        assert_eq!(store.set_rating("a", Some(7.0)).unwrap().user_rating, Some(7.0));
+        assert_eq!(store.get("a").unwrap().unwrap().user_rating, Some(7.0));
+        assert_eq!(store.set_rating("a", None).unwrap().user_rating, None);
+        assert!(store.set_rating("missing", Some(7.0)).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+use crate::error::{AppError, AppResult};
+
+/// Name of the log file in the app log dir, without the `.log` extension
+const LOG_FILE_NAME: &str = "watchfolio";
+const LEVEL_FILE: &str = "log-level.json";
+
+/// Rotate once the file reaches 5 MB
+const MAX_FILE_SIZE: u128 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one
+const KEPT_FILES: usize = 5;
+const DEFAULT_TAIL_LINES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+
+    fn from_filter(filter: LevelFilter) -> Self {
+        match filter {
+            LevelFilter::Off | LevelFilter::Error => LogLevel::Error,
+            LevelFilter::Warn => LogLevel::Warn,
+            LevelFilter::Info => LogLevel::Info,
+            LevelFilter::Debug => LogLevel::Debug,
+            LevelFilter::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl Default for LogLevel {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            LogLevel::Debug
+        } else {
+            LogLevel::Info
+        }
+    }
+}
+
+/// Log to stdout and to a rotating file in the app log dir, in every build.
+/// The dispatcher lets everything through; the runtime level is applied with `log::set_max_level`.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_log::Builder::new()
+        .clear_targets()
+        .target(Target::new(TargetKind::Stdout))
+        .target(Target::new(TargetKind::LogDir {
+            file_name: Some(LOG_FILE_NAME.to_string()),
+        }))
+        .level(LevelFilter::Trace)
+        .max_file_size(MAX_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(KEPT_FILES))
+        .timezone_strategy(TimezoneStrategy::UseLocal)
+        .build()
+}
+
+/// Apply the persisted level; call right after the plugin is attached
+pub fn init(app: &AppHandle) {
+    let level = level_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    apply(level);
+    log::info!(
+        "{} {} starting, log level {:?}",
+        app.package_info().name,
+        app.package_info().version,
+        level
+    );
+}
+
+fn apply(level: LogLevel) {
+    log::set_max_level(level.filter());
+}
+
+fn level_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(LEVEL_FILE))
+}
+
+pub fn log_file(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(app.path().app_log_dir()?.join(format!("{}.log", LOG_FILE_NAME)))
+}
+
+/// The last `lines` lines of the current log file
+pub fn tail(app: &AppHandle, lines: usize) -> AppResult<String> {
+    tail_file(&log_file(app)?, lines)
+}
+
+/// Like `tail`, for callers that can't reach the app, such as the panic hook
+pub fn tail_file(path: &Path, lines: usize) -> AppResult<String> {
+    let content = match std::fs::read(path) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+        Err(e) => return Err(AppError::from(e).context(&format!("Failed to read {}", path.display()))),
+    };
+    let all: Vec<&str> = content.lines().collect();
+    Ok(all[all.len().saturating_sub(lines)..].join("\n"))
+}
+
+/// Read the end of the log file, 500 lines unless `lines` says otherwise
+#[tauri::command]
+pub fn read_logs(app: AppHandle, lines: Option<usize>) -> AppResult<String> {
+    tail(&app, lines.unwrap_or(DEFAULT_TAIL_LINES))
+}
+
+#[tauri::command]
+pub fn get_log_level() -> LogLevel {
+    LogLevel::from_filter(log::max_level())
+}
+
+/// Change the level right away and keep it for the next launches
+#[tauri::command]
+pub fn set_log_level(app: AppHandle, level: LogLevel) -> AppResult<LogLevel> {
+    apply(level);
+    log::info!("Log level set to {:?}", level);
+
+    let path = level_path(&app).ok_or_else(|| AppError::unavailable("App config directory is not available"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let content = serde_json::to_string(&level)?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, &path)?;
+    Ok(level)
+}
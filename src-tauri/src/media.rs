@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+
+/// Mirrors `MediaType` in `src/types/Library.types.d.ts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaType {
+    Movie,
+    Tv,
+}
+
+impl MediaType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaType::Movie => "movie",
+            MediaType::Tv => "tv",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "movie" => Some(MediaType::Movie),
+            "tv" => Some(MediaType::Tv),
+            _ => None,
+        }
+    }
+}
+
+/// Mirrors `WatchStatus` in `src/types/Library.types.d.ts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchStatus {
+    Watching,
+    WillWatch,
+    Completed,
+    OnHold,
+    Dropped,
+    #[default]
+    None,
+}
+
+impl WatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchStatus::Watching => "watching",
+            WatchStatus::WillWatch => "willWatch",
+            WatchStatus::Completed => "completed",
+            WatchStatus::OnHold => "onHold",
+            WatchStatus::Dropped => "dropped",
+            WatchStatus::None => "none",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "watching" => Some(WatchStatus::Watching),
+            "willWatch" => Some(WatchStatus::WillWatch),
+            "completed" => Some(WatchStatus::Completed),
+            "onHold" => Some(WatchStatus::OnHold),
+            "dropped" => Some(WatchStatus::Dropped),
+            "none" => Some(WatchStatus::None),
+            _ => None,
+        }
+    }
+}
+
+/// A library entry, serialized exactly like `LibraryMedia` on the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryMedia {
+    pub id: String,
+    #[serde(default)]
+    pub status: WatchStatus,
+    #[serde(default)]
+    pub is_favorite: bool,
+    pub user_rating: Option<f64>,
+    pub notes: Option<String>,
+    pub added_at: String,
+    pub last_updated_at: String,
+
+    // TMDB media fields
+    pub tmdb_id: u64,
+    #[serde(rename = "media_type")]
+    pub media_type: MediaType,
+    pub title: String,
+    pub overview: Option<String>,
+    pub poster_path: Option<String>,
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<u32>,
+    pub rating: Option<f64>,
+    pub total_minutes_runtime: Option<i64>,
+    #[serde(default)]
+    pub networks: Vec<u32>,
+
+    pub library: Option<String>,
+    pub user_id: Option<String>,
+}
+
+/// Same as `slugify` in `src/utils/index.ts`
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut previous_lowercase = false;
+    for c in text.chars() {
+        if previous_lowercase && c.is_ascii_uppercase() {
+            slug.push('-');
+        }
+        previous_lowercase = c.is_ascii_lowercase();
+        match c.to_ascii_lowercase() {
+            ' ' => slug.push('-'),
+            c @ ('a'..='z' | '0'..='9' | '-') => slug.push(c),
+            _ => {}
+        }
+    }
+    slug
+}
+
+/// Route of a title's details page, as built by `generateMediaLink` in `src/utils/media.ts`
+pub fn details_path(media_type: MediaType, tmdb_id: u64, title: &str) -> String {
+    let section = match media_type {
+        MediaType::Movie => "movies",
+        MediaType::Tv => "tv",
+    };
+    format!("/{}/details/{}-{}", section, tmdb_id, slugify(title))
+}
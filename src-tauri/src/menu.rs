@@ -1,4 +1,52 @@
-use tauri::{AppHandle, Manager, Emitter, menu::*};
+use tauri::{AppHandle, Manager, Emitter, Wry, menu::*};
+
+use crate::shortcuts::{self, ShortcutAction};
+
+/// Go menu items that advertise a global shortcut chord
+const GO_SHORTCUTS: [(&str, &str, &str); 5] = [
+    ("go_home", "Home", "/home"),
+    ("go_library", "Library", "/library/all"),
+    ("go_movies", "Movies", "/movies"),
+    ("go_tv", "TV Shows", "/tv"),
+    ("go_search", "Search", "/search"),
+];
+
+/// Handles to the menu items that show a rebindable shortcut
+pub struct ShortcutMenuItems(Vec<(MenuItem<Wry>, &'static str, ShortcutAction)>);
+
+fn go_action(path: &str) -> ShortcutAction {
+    match path {
+        "/search" => ShortcutAction::OpenSearch,
+        path => ShortcutAction::Navigate(path.to_string()),
+    }
+}
+
+/// Show `action`'s binding on `item`: a single combination as the native accelerator, a chord,
+/// which can't be one, after the label
+fn show_shortcut(item: &MenuItem<Wry>, app: &AppHandle, label: &str, action: &ShortcutAction) -> tauri::Result<()> {
+    let accelerator = shortcuts::accelerator_for(app, action);
+    match accelerator.as_deref() {
+        Some(chord) if chord.contains(char::is_whitespace) => {
+            item.set_text(format!("{} ({})", label, chord))?;
+            item.set_accelerator(None::<&str>)
+        }
+        accelerator => {
+            item.set_text(label)?;
+            item.set_accelerator(accelerator)
+        }
+    }
+}
+
+/// Show the current bindings after shortcuts are rebound
+pub fn refresh_shortcut_labels(app: &AppHandle) {
+    if let Some(items) = app.try_state::<ShortcutMenuItems>() {
+        for (item, label, action) in &items.0 {
+            if let Err(e) = show_shortcut(item, app, label, action) {
+                log::warn!("Failed to show shortcut for menu item {}: {}", label, e);
+            }
+        }
+    }
+}
 
 pub fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
     // File Menu
@@ -61,11 +109,15 @@ pub fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
         .build()?;
 
     // Go Menu
-    let go_home = MenuItem::with_id(app, "go_home", "Home", true, Some("G+H"))?;
-    let go_library = MenuItem::with_id(app, "go_library", "Library", true, Some("G+L"))?;
-    let go_movies = MenuItem::with_id(app, "go_movies", "Movies", true, Some("G+M"))?;
-    let go_tv = MenuItem::with_id(app, "go_tv", "TV Shows", true, Some("G+T"))?;
-    let go_search = MenuItem::with_id(app, "go_search", "Search", true, Some("G+S"))?;
+    let mut go_items = Vec::new();
+    for (id, label, path) in GO_SHORTCUTS {
+        let action = go_action(path);
+        let item = MenuItem::with_id(app, id, label, true, None::<&str>)?;
+        if let Err(e) = show_shortcut(&item, app, label, &action) {
+            log::warn!("Failed to show shortcut for menu item {}: {}", label, e);
+        }
+        go_items.push((item, label, action));
+    }
     let go_mood = MenuItem::with_id(app, "go_mood", "Mood Match", true, None::<&str>)?;
     let go_collections = MenuItem::with_id(app, "go_collections", "Collections", true, None::<&str>)?;
     let go_celebrities = MenuItem::with_id(app, "go_celebrities", "Celebrities", true, None::<&str>)?;
@@ -73,12 +125,11 @@ pub fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
     let go_back = MenuItem::with_id(app, "go_back", "Back", true, Some("Alt+Left"))?;
     let go_forward = MenuItem::with_id(app, "go_forward", "Forward", true, Some("Alt+Right"))?;
 
-    let go_menu = SubmenuBuilder::new(app, "Go")
-        .item(&go_home)
-        .item(&go_library)
-        .item(&go_movies)
-        .item(&go_tv)
-        .item(&go_search)
+    let mut go_menu = SubmenuBuilder::new(app, "Go");
+    for (item, ..) in &go_items {
+        go_menu = go_menu.item(item);
+    }
+    let go_menu = go_menu
         .separator()
         .item(&go_mood)
         .item(&go_collections)
@@ -92,6 +143,8 @@ pub fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
     // Help Menu
     let keyboard_shortcuts = MenuItem::with_id(app, "keyboard_shortcuts", "Keyboard Shortcuts", true, Some("?"))?;
     let report_issue = MenuItem::with_id(app, "report_issue", "Report Issue", true, None::<&str>)?;
+    let export_diagnostics =
+        MenuItem::with_id(app, "export_diagnostics", "Export Diagnostics…", true, None::<&str>)?;
     let check_updates = MenuItem::with_id(app, "check_updates", "Check for Updates", true, None::<&str>)?;
     let about = MenuItem::with_id(app, "about", "About Watchfolio", true, None::<&str>)?;
 
@@ -99,11 +152,14 @@ pub fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
         .item(&keyboard_shortcuts)
         .separator()
         .item(&report_issue)
+        .item(&export_diagnostics)
         .item(&check_updates)
         .separator()
         .item(&about)
         .build()?;
 
+    app.manage(ShortcutMenuItems(go_items));
+
     // Build main menu
     let menu = MenuBuilder::new(app)
         .item(&file_menu)
@@ -117,7 +173,10 @@ pub fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, tauri::Error> {
 }
 
 pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
-    let window = app.get_webview_window("main").unwrap();
+    let Some(window) = app.get_webview_window("main") else {
+        log::warn!("Main window is gone, ignoring menu event {:?}", event.id());
+        return;
+    };
 
     match event.id().as_ref() {
         // File menu
@@ -166,7 +225,7 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
             let _ = window.emit("menu:navigate", "/library/completed");
         }
         "library_plan" => {
-            let _ = window.emit("menu:navigate", "/library/plan-to-watch");
+            let _ = window.emit("menu:navigate", "/library/will-watch");
         }
         "library_hold" => {
             let _ = window.emit("menu:navigate", "/library/on-hold");
@@ -223,12 +282,8 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         "keyboard_shortcuts" => {
             let _ = window.emit("menu:keyboard-shortcuts", ());
         }
-        "report_issue" => {
-            let _ = tauri_plugin_opener::open_url(
-                "https://github.com/zawalid/watchfolio/issues",
-                None::<&str>
-            );
-        }
+        "report_issue" => crate::diagnostics::open_issue(app),
+        "export_diagnostics" => crate::diagnostics::export_with_dialog(app),
         "check_updates" => {
             let _ = window.emit("menu:check-updates", ());
         }
@@ -1,89 +1,1067 @@
-use tauri::{AppHandle, Manager, Emitter};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Emitter, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::media::WatchStatus;
+
+const SHORTCUTS_FILE: &str = "shortcuts.json";
+/// 1: actions were free-form strings; 2: typed `ShortcutAction`s
+const REGISTRY_VERSION: u32 = 2;
+
+/// How long a chord leader waits for its second key
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Leader of the default Go chords
+pub const CHORD_LEADER: &str = "Ctrl+Shift+Space";
+
+/// What a global shortcut does, run natively so it works while the webview is hidden
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum ShortcutAction {
+    ToggleMain,
+    QuickAdd,
+    OpenSearch,
+    /// Show the main window on a route, e.g. `/library/watching`
+    Navigate(String),
+    SyncNow,
+    /// Set the status of the title open in the main window
+    MarkCurrent(WatchStatus),
+}
+
+impl ShortcutAction {
+    /// Reject actions that could never run, so they fail at registration rather than on key press
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            ShortcutAction::Navigate(path) if !path.starts_with('/') || path.chars().any(char::is_whitespace) => {
+                Err(format!("Invalid route \"{}\", expected an app path such as /library", path))
+            }
+            ShortcutAction::MarkCurrent(WatchStatus::None) => {
+                Err("Can't mark the current title with status \"none\"".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Map a version 1 string action; unknown custom actions have no native equivalent
+    fn from_legacy(action: &str) -> Option<Self> {
+        match action {
+            "quickAdd" => Some(ShortcutAction::QuickAdd),
+            "toggleMain" => Some(ShortcutAction::ToggleMain),
+            path if path.starts_with('/') => Some(ShortcutAction::Navigate(path.to_string())),
+            _ => None,
+        }
+    }
+
+    fn run(&self, app: &AppHandle) {
+        match self {
+            ShortcutAction::QuickAdd => {
+                if let Some(quick_add_window) = app.get_webview_window("quick-add") {
+                    // Always show and focus the window
+                    let _ = quick_add_window.show();
+                    let _ = quick_add_window.unminimize();
+                    let _ = quick_add_window.set_focus();
+                    let _ = quick_add_window.center();
+                }
+            }
+            ShortcutAction::ToggleMain => {
+                if let Some(window) = app.get_webview_window("main") {
+                    if window.is_visible().unwrap_or(false) {
+                        let _ = window.hide();
+                    } else {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+            }
+            ShortcutAction::OpenSearch => navigate(app, "/search"),
+            ShortcutAction::Navigate(path) => navigate(app, path),
+            ShortcutAction::SyncNow => {
+                // The sync engine lives in the webview, which keeps running while hidden
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.emit("shortcut:sync-now", ());
+                }
+            }
+            ShortcutAction::MarkCurrent(status) => crate::tray::mark_current(app, *status),
+        }
+    }
+}
+
+impl fmt::Display for ShortcutAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutAction::ToggleMain => write!(f, "Show/Hide Watchfolio"),
+            ShortcutAction::QuickAdd => write!(f, "Quick Add"),
+            ShortcutAction::OpenSearch => write!(f, "Search"),
+            ShortcutAction::Navigate(path) => write!(f, "Go to {}", path),
+            ShortcutAction::SyncNow => write!(f, "Sync Now"),
+            ShortcutAction::MarkCurrent(status) => write!(f, "Mark as {}", status.as_str()),
+        }
+    }
+}
+
+fn navigate(app: &AppHandle, path: &str) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        let _ = window.emit("shortcut:navigate", path);
+    }
+}
+
+/// Built-in actions and the accelerators they start with
+fn default_bindings() -> Vec<ShortcutBinding> {
+    let mut bindings = vec![
+        // Works anywhere - shows standalone Quick Add window
+        ShortcutBinding {
+            accelerator: "Ctrl+Shift+A".to_string(),
+            action: ShortcutAction::QuickAdd,
+        },
+        // Toggles app window visibility from anywhere
+        ShortcutBinding {
+            accelerator: "Ctrl+Shift+W".to_string(),
+            action: ShortcutAction::ToggleMain,
+        },
+    ];
+
+    // Go chords: the leader, then one key
+    let go = [
+        ("H", ShortcutAction::Navigate("/home".to_string())),
+        ("L", ShortcutAction::Navigate("/library/all".to_string())),
+        ("M", ShortcutAction::Navigate("/movies".to_string())),
+        ("T", ShortcutAction::Navigate("/tv".to_string())),
+        ("S", ShortcutAction::OpenSearch),
+    ];
+    bindings.extend(go.into_iter().map(|(key, action)| ShortcutBinding {
+        accelerator: format!("{} {}", CHORD_LEADER, key),
+        action,
+    }));
+
+    bindings
+}
+
+/// One accelerator mapped to one action, as stored in `shortcuts.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub accelerator: String,
+    pub action: ShortcutAction,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ShortcutsFile {
+    version: u32,
+    bindings: Vec<ShortcutBinding>,
+}
+
+#[derive(Deserialize)]
+struct LegacyBinding {
+    accelerator: String,
+    action: String,
+}
+
+#[derive(Deserialize)]
+struct LegacyShortcutsFile {
+    bindings: Vec<LegacyBinding>,
+}
+
+/// Read any known version of `shortcuts.json` into current bindings
+fn parse_shortcuts_file(content: &str) -> Result<Vec<ShortcutBinding>, String> {
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+
+    let Version { version } = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    match version {
+        1 => {
+            let file: LegacyShortcutsFile = serde_json::from_str(content).map_err(|e| e.to_string())?;
+            Ok(file
+                .bindings
+                .into_iter()
+                .filter_map(|binding| match ShortcutAction::from_legacy(&binding.action) {
+                    Some(action) => Some(ShortcutBinding {
+                        accelerator: binding.accelerator,
+                        action,
+                    }),
+                    None => {
+                        log::warn!(
+                            "Dropping shortcut {} with unsupported action \"{}\"",
+                            binding.accelerator,
+                            binding.action
+                        );
+                        None
+                    }
+                })
+                .collect())
+        }
+        REGISTRY_VERSION => serde_json::from_str::<ShortcutsFile>(content)
+            .map(|file| file.bindings)
+            .map_err(|e| e.to_string()),
+        version => Err(format!("unknown version {}", version)),
+    }
+}
+
+/// Whether a binding is actually live with the OS
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum RegistrationStatus {
+    Registered,
+    /// Another binding earlier in the registry uses the same accelerator
+    Conflict { action: ShortcutAction },
+    /// The accelerator is invalid or the OS refused it, usually because another app owns it
+    Failed { message: String },
+    /// Not registered yet
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutInfo {
+    pub accelerator: String,
+    pub action: ShortcutAction,
+    #[serde(flatten)]
+    pub status: RegistrationStatus,
+}
+
+/// A parsed accelerator: a single combination, or a leader followed by one more key
+#[derive(Debug, Clone, Copy)]
+struct Keys {
+    leader: Shortcut,
+    then: Option<Shortcut>,
+}
+
+impl Keys {
+    /// `Ctrl+Shift+A` is a plain shortcut; `Ctrl+Shift+Space H` is a chord
+    fn parse(accelerator: &str) -> Result<Self, String> {
+        let steps: Vec<&str> = accelerator.split_whitespace().collect();
+        match steps.as_slice() {
+            [single] => Ok(Self {
+                leader: parse_shortcut(single)?,
+                then: None,
+            }),
+            [leader, then] => {
+                let leader = parse_shortcut(leader)?;
+                if leader.mods.is_empty() {
+                    return Err(format!(
+                        "Chord leader \"{}\" needs a modifier such as Ctrl or Alt",
+                        steps[0]
+                    ));
+                }
+                Ok(Self {
+                    leader,
+                    then: Some(parse_shortcut(then)?),
+                })
+            }
+            [] => Err("Empty shortcut".to_string()),
+            _ => Err(format!(
+                "Invalid chord \"{}\", expected a leader and one key such as \"Ctrl+Shift+Space H\"",
+                accelerator
+            )),
+        }
+    }
+
+    /// Two bindings clash when pressing one could trigger the other
+    fn clashes(&self, other: &Keys) -> bool {
+        self.leader.id() == other.leader.id()
+            && match (self.then, other.then) {
+                (Some(a), Some(b)) => a.id() == b.id(),
+                _ => true,
+            }
+    }
+}
+
+struct Entry {
+    binding: ShortcutBinding,
+    keys: Option<Keys>,
+    status: RegistrationStatus,
+}
+
+impl Entry {
+    fn new(binding: ShortcutBinding) -> Self {
+        let keys = Keys::parse(&binding.accelerator);
+        let status = match keys.as_ref().map_err(Clone::clone).and_then(|_| binding.action.validate()) {
+            Ok(()) => RegistrationStatus::Pending,
+            Err(message) => RegistrationStatus::Failed { message },
+        };
+        Self {
+            binding,
+            keys: keys.ok(),
+            status,
+        }
+    }
+
+    fn info(&self) -> ShortcutInfo {
+        ShortcutInfo {
+            accelerator: self.binding.accelerator.clone(),
+            action: self.binding.action.clone(),
+            status: self.status.clone(),
+        }
+    }
+
+    fn is_registered(&self) -> bool {
+        matches!(self.status, RegistrationStatus::Registered)
+    }
+
+    fn has_leader(&self, shortcut: &Shortcut) -> bool {
+        self.keys.is_some_and(|keys| keys.leader.id() == shortcut.id())
+    }
+}
+
+/// Follow-up keys that are live while a chord leader is armed
+struct ArmedChord {
+    generation: u64,
+    keys: Vec<(Shortcut, ShortcutAction)>,
+}
+
+/// What a pressed combination means to the registry
+enum Pressed {
+    Action(ShortcutAction),
+    Leader(Vec<(Shortcut, ShortcutAction)>),
+}
+
+/// Global shortcuts, persisted in the app config dir and re-registered at startup.
+/// The plugin runs register/unregister on the main thread and waits for it, while `handle_shortcut`
+/// locks `entries` on that same thread: never talk to the plugin with `entries` locked.
+pub struct ShortcutRegistry {
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<Entry>>,
+    /// Held for a whole rebind, reset or unbind, so they don't interleave while `entries` is unlocked
+    changes: Mutex<()>,
+    armed: Mutex<Option<ArmedChord>>,
+    generation: AtomicU64,
+}
+
+/// `Keys::parse` for commands, where a bad accelerator is the caller's mistake
+fn parse_keys(accelerator: &str) -> AppResult<Keys> {
+    Keys::parse(accelerator).map_err(|message| {
+        AppError::new(ErrorCode::InvalidShortcut, message)
+            .with_details(serde_json::json!({ "accelerator": accelerator }))
+    })
+}
+
+fn plugin_error(e: impl fmt::Display) -> AppError {
+    AppError::internal(e.to_string())
+}
+
+fn parse_shortcut(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut: {}", e))
+}
+
+impl ShortcutRegistry {
+    /// Read `shortcuts.json`, falling back to the defaults when it is missing or unreadable
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(SHORTCUTS_FILE));
+
+        let bindings = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => match parse_shortcuts_file(&content) {
+                Ok(bindings) => bindings,
+                Err(e) => {
+                    log::warn!("Failed to parse shortcuts file, using defaults: {}", e);
+                    default_bindings()
+                }
+            },
+            _ => default_bindings(),
+        };
+
+        Self {
+            path,
+            entries: Mutex::new(bindings.into_iter().map(Entry::new).collect()),
+            changes: Mutex::new(()),
+            armed: Mutex::new(None),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    fn save(&self, entries: &[Entry]) -> AppResult<()> {
+        let Some(path) = &self.path else {
+            return Err(AppError::unavailable("App config directory is not available"));
+        };
+        let file = ShortcutsFile {
+            version: REGISTRY_VERSION,
+            bindings: entries.iter().map(|entry| entry.binding.clone()).collect(),
+        };
+        let content = serde_json::to_string_pretty(&file)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, content)?;
+        Ok(std::fs::rename(&temp, path)?)
+    }
+
+    /// Resolve a pressed combination against the live bindings
+    fn pressed(&self, shortcut: &Shortcut) -> Option<Pressed> {
+        let entries = self.entries.lock().ok()?;
+        let mut chord_keys = Vec::new();
+
+        for entry in entries.iter().filter(|entry| entry.is_registered() && entry.has_leader(shortcut)) {
+            match entry.keys.and_then(|keys| keys.then) {
+                None => return Some(Pressed::Action(entry.binding.action.clone())),
+                Some(then) => chord_keys.push((then, entry.binding.action.clone())),
+            }
+        }
+
+        (!chord_keys.is_empty()).then_some(Pressed::Leader(chord_keys))
+    }
+
+    /// Listen for the second key of a chord until it is pressed or the timeout passes
+    fn arm(&self, app: &AppHandle, keys: Vec<(Shortcut, ShortcutAction)>) {
+        self.disarm(app, None);
+
+        for (then, _) in &keys {
+            // A follow-up that is also a plain binding is already registered and still reaches us
+            let _ = app.global_shortcut().register(*then);
+        }
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Ok(mut armed) = self.armed.lock() {
+            *armed = Some(ArmedChord { generation, keys });
+        }
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(CHORD_TIMEOUT).await;
+            if let Some(registry) = app.try_state::<ShortcutRegistry>() {
+                registry.disarm(&app, Some(generation));
+            }
+        });
+    }
+
+    /// Stop listening for chord keys; with `generation`, only if that arming is still current
+    fn disarm(&self, app: &AppHandle, generation: Option<u64>) -> Option<ArmedChord> {
+        let armed = {
+            let mut armed = self.armed.lock().ok()?;
+            if generation.is_some_and(|generation| armed.as_ref().map(|a| a.generation) != Some(generation)) {
+                return None;
+            }
+            armed.take()?
+        };
+
+        // Leave follow-ups that double as plain bindings registered
+        let released: Vec<Shortcut> = {
+            let entries = self.entries.lock().ok();
+            armed
+                .keys
+                .iter()
+                .map(|(then, _)| *then)
+                .filter(|then| {
+                    !entries.as_ref().is_some_and(|entries| {
+                        entries.iter().any(|entry| entry.is_registered() && entry.has_leader(then))
+                    })
+                })
+                .collect()
+        };
+        for then in released {
+            let _ = app.global_shortcut().unregister(then);
+        }
+        Some(armed)
+    }
+
+    /// The chord action for a key pressed while a leader is armed
+    fn take_chord_key(&self, app: &AppHandle, shortcut: &Shortcut) -> Option<ShortcutAction> {
+        let is_chord_key = self
+            .armed
+            .lock()
+            .ok()?
+            .as_ref()?
+            .keys
+            .iter()
+            .any(|(then, _)| then.id() == shortcut.id());
+        if !is_chord_key {
+            return None;
+        }
+
+        self.disarm(app, None)?
+            .keys
+            .into_iter()
+            .find(|(then, _)| then.id() == shortcut.id())
+            .map(|(_, action)| action)
+    }
+}
+
+/// Settle what `entries` alone can decide: a clash with a live entry, or a chord leader another chord
+/// already registered. Otherwise returns the leader the OS still has to grant, leaving the entry `Pending`.
+fn plan_register(entries: &[Entry], entry: &mut Entry) -> Option<Shortcut> {
+    let (Some(keys), RegistrationStatus::Pending) = (entry.keys, &entry.status) else {
+        return None;
+    };
+    let live = || entries.iter().filter(|other| other.is_registered());
+
+    if let Some(other) = live().find(|other| other.keys.is_some_and(|other| other.clashes(&keys))) {
+        entry.status = RegistrationStatus::Conflict {
+            action: other.binding.action.clone(),
+        };
+        return None;
+    }
+
+    // Chords sharing a leader share its OS registration
+    if keys.then.is_some() && live().any(|other| other.has_leader(&keys.leader)) {
+        entry.status = RegistrationStatus::Registered;
+        return None;
+    }
+    Some(keys.leader)
+}
+
+/// Ask the OS for `leader`; only with `entries` unlocked
+fn register_leader(app: &AppHandle, leader: Shortcut) -> RegistrationStatus {
+    match app.global_shortcut().register(leader) {
+        Ok(()) => RegistrationStatus::Registered,
+        Err(e) => RegistrationStatus::Failed {
+            message: format!("{} (it may be used by another application)", e),
+        },
+    }
+}
+
+/// Register one entry that isn't behind the registry lock yet
+fn register_entry(app: &AppHandle, entries: &[Entry], entry: &mut Entry) {
+    if let Some(leader) = plan_register(entries, entry) {
+        entry.status = register_leader(app, leader);
+    }
+}
+
+/// Take an entry out of service. Returns its leader for the caller to unregister once `entries` is
+/// unlocked, unless another live entry in `entries` still uses it.
+fn plan_unregister(entries: &[Entry], entry: &mut Entry) -> Option<Shortcut> {
+    let released = match (entry.is_registered(), entry.keys) {
+        (true, Some(keys)) => {
+            let shared = entries
+                .iter()
+                .any(|other| other.is_registered() && other.has_leader(&keys.leader));
+            (!shared).then_some(keys.leader)
+        }
+        _ => None,
+    };
+    entry.status = RegistrationStatus::Pending;
+    released
+}
+
+fn unregister_leader(app: &AppHandle, leader: Shortcut, accelerator: &str) {
+    if let Err(e) = app.global_shortcut().unregister(leader) {
+        log::warn!("Failed to unregister {}: {}", accelerator, e);
+    }
+}
+
+fn register_entries(app: &AppHandle, entries: &mut [Entry]) {
+    for i in 0..entries.len() {
+        let (before, rest) = entries.split_at_mut(i);
+        register_entry(app, before, &mut rest[0]);
+
+        match &rest[0].status {
+            RegistrationStatus::Registered | RegistrationStatus::Pending => {}
+            RegistrationStatus::Conflict { action } => log::warn!(
+                "Shortcut {} for {} conflicts with {}",
+                rest[0].binding.accelerator,
+                rest[0].binding.action,
+                action
+            ),
+            RegistrationStatus::Failed { message } => log::warn!(
+                "Failed to register shortcut {} for {}: {}",
+                rest[0].binding.accelerator,
+                rest[0].binding.action,
+                message
+            ),
+        }
+    }
+}
 
 /// Register ONLY system-wide global shortcuts
 /// These shortcuts work even when the app is minimized or hidden
 ///
 /// Note: All other shortcuts are handled by React (useHotkeys) when the app is focused
 pub fn register_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Global Shortcut 1: Quick Add (Ctrl+Shift+A)
-    // Works anywhere - shows standalone Quick Add window
-    app.global_shortcut().on_shortcut("Ctrl+Shift+A", {
-        let app = app.clone();
-        move |_app, _shortcut, _event| {
-            if let Some(quick_add_window) = app.get_webview_window("quick-add") {
-                // Always show and focus the window
-                let _ = quick_add_window.show();
-                let _ = quick_add_window.unminimize();
-                let _ = quick_add_window.set_focus();
-                let _ = quick_add_window.center();
-            }
+    let registry = ShortcutRegistry::load(app);
+    // Not managed yet, so no shortcut handler can be waiting on this lock
+    {
+        let mut entries = registry.entries.lock().map_err(AppError::from)?;
+        register_entries(app, &mut entries);
+    }
+    app.manage(registry);
+    Ok(())
+}
+
+/// The live accelerator of an action, for showing it in menus
+pub fn accelerator_for(app: &AppHandle, action: &ShortcutAction) -> Option<String> {
+    let registry = app.try_state::<ShortcutRegistry>()?;
+    let entries = registry.entries.lock().ok()?;
+    entries
+        .iter()
+        .find(|entry| entry.is_registered() && entry.binding.action == *action)
+        .map(|entry| entry.binding.accelerator.clone())
+}
+
+/// Handler for every global shortcut, installed on the plugin in `lib.rs`
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let Some(registry) = app.try_state::<ShortcutRegistry>() else {
+        return;
+    };
+
+    if let Some(action) = registry.take_chord_key(app, shortcut) {
+        action.run(app);
+        return;
+    }
+    match registry.pressed(shortcut) {
+        Some(Pressed::Action(action)) => action.run(app),
+        Some(Pressed::Leader(keys)) => registry.arm(app, keys),
+        None => {}
+    }
+}
+
+/// Point `action` at `accelerator`, adding the binding if the action has none yet
+fn bind(
+    app: &AppHandle,
+    registry: &ShortcutRegistry,
+    action: ShortcutAction,
+    accelerator: String,
+    create: bool,
+) -> AppResult<ShortcutInfo> {
+    let keys = parse_keys(&accelerator)?;
+    action.validate().map_err(AppError::invalid_input)?;
+    registry.disarm(app, None);
+    let _changing = registry.changes.lock()?;
+    let mut entries = registry.entries.lock()?;
+
+    if let Some(other) = entries.iter().find(|entry| {
+        entry.binding.action != action && entry.keys.is_some_and(|other| other.clashes(&keys))
+    }) {
+        return Err(AppError::new(
+            ErrorCode::ShortcutInUse,
+            format!("{} is already bound to {}", accelerator, other.binding.action),
+        )
+        .with_details(serde_json::json!({ "accelerator": accelerator, "action": other.binding.action })));
+    }
+
+    let existing = entries.iter().position(|entry| entry.binding.action == action);
+    let index = match existing {
+        Some(index) => index,
+        None if create => {
+            entries.push(Entry::new(ShortcutBinding {
+                accelerator: accelerator.clone(),
+                action: action.clone(),
+            }));
+            entries.len() - 1
         }
-    })?;
+        None => return Err(AppError::not_found(format!("Unknown shortcut action: {}", action))),
+    };
 
-    // Global Shortcut 2: Show/Hide App (Ctrl+Shift+W)
-    // Toggles app window visibility from anywhere
-    app.global_shortcut().on_shortcut("Ctrl+Shift+W", {
-        let app = app.clone();
-        move |_app, _shortcut, _event| {
-            if let Some(window) = app.get_webview_window("main") {
-                if window.is_visible().unwrap_or(false) {
-                    let _ = window.hide();
-                } else {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+    let mut old = entries.remove(index);
+    let released = plan_unregister(&entries, &mut old);
+    let mut entry = Entry::new(ShortcutBinding {
+        accelerator,
+        action,
+    });
+    let wanted = plan_register(&entries, &mut entry);
+
+    drop(entries);
+    if let Some(leader) = released {
+        unregister_leader(app, leader, &old.binding.accelerator);
+    }
+    if let Some(leader) = wanted {
+        entry.status = register_leader(app, leader);
+    }
+
+    if let RegistrationStatus::Failed { message } = &entry.status {
+        // The OS refusing a combination almost always means another app holds it
+        let error = AppError::new(
+            ErrorCode::ShortcutInUse,
+            format!("Couldn't register {}: {}", entry.binding.accelerator, message),
+        )
+        .with_details(serde_json::json!({ "accelerator": entry.binding.accelerator }));
+        if existing.is_some() {
+            // Keep the old binding live rather than leaving the action unbound
+            let wanted = plan_register(&registry.entries.lock()?, &mut old);
+            if let Some(leader) = wanted {
+                old.status = register_leader(app, leader);
             }
+            registry.entries.lock()?.insert(index, old);
         }
-    })?;
+        return Err(error);
+    }
 
-    // Register the shortcuts (2 active shortcuts)
-    app.global_shortcut().register("Ctrl+Shift+A")?;
-    app.global_shortcut().register("Ctrl+Shift+W")?;
+    let info = entry.info();
+    let mut entries = registry.entries.lock()?;
+    entries.insert(index, entry);
+    registry.save(&entries)?;
+    drop(entries);
 
-    Ok(())
+    crate::menu::refresh_shortcut_labels(app);
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn list_shortcuts(registry: State<ShortcutRegistry>) -> AppResult<Vec<ShortcutInfo>> {
+    let entries = registry.entries.lock()?;
+    Ok(entries.iter().map(Entry::info).collect())
 }
 
+/// Move an existing action to a new accelerator or chord
+#[tauri::command]
+pub fn rebind_shortcut(
+    app: AppHandle,
+    registry: State<ShortcutRegistry>,
+    action: ShortcutAction,
+    accelerator: String,
+) -> AppResult<ShortcutInfo> {
+    bind(&app, &registry, action, accelerator, false)
+}
+
+/// Drop every custom binding and restore the default accelerators
+#[tauri::command]
+pub fn reset_shortcuts(
+    app: AppHandle,
+    registry: State<ShortcutRegistry>,
+) -> AppResult<Vec<ShortcutInfo>> {
+    registry.disarm(&app, None);
+    let infos = {
+        let _changing = registry.changes.lock()?;
+        app.global_shortcut().unregister_all().map_err(plugin_error)?;
+
+        // Registered outside the lock, then swapped in whole
+        let mut defaults: Vec<Entry> = default_bindings().into_iter().map(Entry::new).collect();
+        register_entries(&app, &mut defaults);
+        let mut entries = registry.entries.lock()?;
+        *entries = defaults;
+        registry.save(&entries)?;
+        entries.iter().map(Entry::info).collect()
+    };
+
+    crate::menu::refresh_shortcut_labels(&app);
+    Ok(infos)
+}
+
+/// Bind `shortcut`, either a combination like `Ctrl+Alt+L` or a chord like `Ctrl+Shift+Space L`
 #[tauri::command]
 pub async fn register_custom_shortcut(
     app: AppHandle,
     shortcut: String,
-    action: String,
-) -> Result<(), String> {
-    let shortcut_obj: Shortcut = shortcut.parse().map_err(|e| format!("Invalid shortcut: {}", e))?;
+    action: ShortcutAction,
+) -> AppResult<()> {
+    let registry = app
+        .try_state::<ShortcutRegistry>()
+        .ok_or_else(|| AppError::unavailable("Shortcuts are not available"))?;
+    bind(&app, &registry, action, shortcut, true).map(|_| ())
+}
 
-    app.global_shortcut()
-        .on_shortcut(shortcut_obj.clone(), {
-            let app = app.clone();
-            move |_app, _shortcut, _event| {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.emit("shortcut:custom", &action);
+#[tauri::command]
+pub async fn unregister_shortcut(app: AppHandle, shortcut: String) -> AppResult<()> {
+    let keys = parse_keys(&shortcut)?;
+    let registry = app
+        .try_state::<ShortcutRegistry>()
+        .ok_or_else(|| AppError::unavailable("Shortcuts are not available"))?;
+    registry.disarm(&app, None);
+
+    {
+        let _changing = registry.changes.lock()?;
+        let mut entries = registry.entries.lock()?;
+        let index = entries.iter().position(|entry| {
+            entry.keys.is_some_and(|other| {
+                other.leader.id() == keys.leader.id() && other.then.map(|s| s.id()) == keys.then.map(|s| s.id())
+            })
+        });
+
+        match index {
+            Some(index) => {
+                let mut entry = entries.remove(index);
+                let released = plan_unregister(&entries, &mut entry);
+                registry.save(&entries)?;
+                drop(entries);
+                if let Some(leader) = released {
+                    unregister_leader(&app, leader, &entry.binding.accelerator);
                 }
             }
-        })
-        .map_err(|e| e.to_string())?;
-
-    app.global_shortcut()
-        .register(shortcut_obj)
-        .map_err(|e| e.to_string())?;
+            None if keys.then.is_none() => {
+                drop(entries);
+                return app.global_shortcut().unregister(keys.leader).map_err(plugin_error);
+            }
+            None => return Err(AppError::not_found(format!("No chord bound to {}", shortcut))),
+        }
+    }
 
+    crate::menu::refresh_shortcut_labels(&app);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn unregister_shortcut(app: AppHandle, shortcut: String) -> Result<(), String> {
-    let shortcut_obj: Shortcut = shortcut.parse().map_err(|e| format!("Invalid shortcut: {}", e))?;
+pub async fn is_shortcut_registered(app: AppHandle, shortcut: String) -> AppResult<bool> {
+    let keys = parse_keys(&shortcut)?;
+    match keys.then {
+        None => Ok(app.global_shortcut().is_registered(keys.leader)),
+        Some(_) => {
+            let Some(registry) = app.try_state::<ShortcutRegistry>() else {
+                return Ok(false);
+            };
+            let entries = registry.entries.lock()?;
+            Ok(entries.iter().any(|entry| {
+                entry.is_registered() && entry.keys.is_some_and(|other| other.clashes(&keys) && other.then.is_some())
+            }))
+        }
+    }
+}
 
-    app.global_shortcut()
-        .unregister(shortcut_obj)
-        .map_err(|e| e.to_string())?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-    Ok(())
-}
+    fn binding(accelerator: &str, action: ShortcutAction) -> ShortcutBinding {
+        ShortcutBinding {
+            accelerator: accelerator.to_string(),
+            action,
+        }
+    }
 
-#[tauri::command]
-pub async fn is_shortcut_registered(app: AppHandle, shortcut: String) -> Result<bool, String> {
-    let shortcut_obj: Shortcut = shortcut.parse().map_err(|e| format!("Invalid shortcut: {}", e))?;
+    fn parsed(file: serde_json::Value) -> Result<Vec<(String, ShortcutAction)>, String> {
+        parse_shortcuts_file(&file.to_string()).map(|bindings| {
+            bindings
+                .into_iter()
+                .map(|binding| (binding.accelerator, binding.action))
+                .collect()
+        })
+    }
+
+    fn failure(entry: &Entry) -> Option<&str> {
+        match &entry.status {
+            RegistrationStatus::Failed { message } => Some(message),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn migrates_version_1_string_actions() {
+        let bindings = parsed(json!({
+            "version": 1,
+            "bindings": [
+                { "accelerator": "Ctrl+Shift+A", "action": "quickAdd" },
+                { "accelerator": "Ctrl+Shift+W", "action": "toggleMain" },
+                { "accelerator": "Ctrl+Alt+L", "action": "/library/watching" },
+                { "accelerator": "Ctrl+Alt+X", "action": "somethingCustom" },
+            ],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            bindings,
+            [
+                ("Ctrl+Shift+A".to_string(), ShortcutAction::QuickAdd),
+                ("Ctrl+Shift+W".to_string(), ShortcutAction::ToggleMain),
+                (
+                    "Ctrl+Alt+L".to_string(),
+                    ShortcutAction::Navigate("/library/watching".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_what_it_writes() {
+        let bindings = vec![
+            binding("Ctrl+Shift+A", ShortcutAction::QuickAdd),
+            binding("Ctrl+Alt+C", ShortcutAction::MarkCurrent(WatchStatus::Completed)),
+            binding("Ctrl+Shift+Space L", ShortcutAction::Navigate("/library/all".to_string())),
+        ];
+        let content = serde_json::to_string(&ShortcutsFile {
+            version: REGISTRY_VERSION,
+            bindings: bindings.clone(),
+        })
+        .unwrap();
+
+        let read: Vec<_> = parse_shortcuts_file(&content)
+            .unwrap()
+            .into_iter()
+            .map(|binding| (binding.accelerator, binding.action))
+            .collect();
+        let expected: Vec<_> = bindings
+            .into_iter()
+            .map(|binding| (binding.accelerator, binding.action))
+            .collect();
+        assert_eq!(read, expected);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        assert_eq!(
+            parsed(json!({ "version": REGISTRY_VERSION + 1, "bindings": [] })),
+            Err(format!("unknown version {}", REGISTRY_VERSION + 1))
+        );
+        assert!(parsed(json!({ "bindings": [] })).is_err());
+        assert!(parse_shortcuts_file("not json").is_err());
+    }
+
+    #[test]
+    fn rejects_a_version_2_file_with_string_actions() {
+        let file = json!({
+            "version": 2,
+            "bindings": [{ "accelerator": "Ctrl+Shift+A", "action": "quickAdd" }],
+        });
+        assert!(parsed(file).is_err());
+    }
+
+    #[test]
+    fn invalid_accelerators_fail_without_registering() {
+        for accelerator in ["", "Ctrl+Shift+Nope", "Ctrl++", "Ctrl+Shift+Space H J"] {
+            let entry = Entry::new(binding(accelerator, ShortcutAction::QuickAdd));
+            assert!(failure(&entry).is_some(), "{:?} was accepted", accelerator);
+            assert!(entry.keys.is_none());
+        }
+
+        let entry = Entry::new(binding("Ctrl+Shift+A", ShortcutAction::QuickAdd));
+        assert!(matches!(entry.status, RegistrationStatus::Pending));
+    }
+
+    #[test]
+    fn invalid_actions_fail_without_registering() {
+        let entry = Entry::new(binding("Ctrl+Alt+L", ShortcutAction::Navigate("library".to_string())));
+        assert!(failure(&entry).unwrap().contains("Invalid route"));
+
+        let entry = Entry::new(binding("Ctrl+Alt+N", ShortcutAction::MarkCurrent(WatchStatus::None)));
+        assert!(failure(&entry).is_some());
+    }
+
+    #[test]
+    fn default_bindings_are_valid_and_distinct() {
+        let entries: Vec<Entry> = default_bindings().into_iter().map(Entry::new).collect();
+        for entry in &entries {
+            assert!(failure(entry).is_none(), "{} failed", entry.binding.accelerator);
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let keys = entry.keys.unwrap();
+            assert!(!entries[..i].iter().any(|other| other.keys.unwrap().clashes(&keys)));
+        }
+    }
+
+    fn keys(accelerator: &str) -> Keys {
+        Keys::parse(accelerator).unwrap()
+    }
+
+    fn shortcut(accelerator: &str) -> Shortcut {
+        parse_shortcut(accelerator).unwrap()
+    }
+
+    /// An entry the OS has already granted
+    fn registered(accelerator: &str, action: ShortcutAction) -> Entry {
+        let mut entry = Entry::new(binding(accelerator, action));
+        entry.status = RegistrationStatus::Registered;
+        entry
+    }
+
+    fn go(path: &str) -> ShortcutAction {
+        ShortcutAction::Navigate(path.to_string())
+    }
+
+    #[test]
+    fn parses_plain_shortcuts_and_chords() {
+        let plain = keys("Ctrl+Shift+A");
+        assert_eq!(plain.leader.id(), shortcut("Ctrl+Shift+A").id());
+        assert!(plain.then.is_none());
+
+        let chord = keys("Ctrl+Shift+Space  H");
+        assert_eq!(chord.leader.id(), shortcut("Ctrl+Shift+Space").id());
+        assert_eq!(chord.then.map(|then| then.id()), Some(shortcut("H").id()));
+    }
+
+    #[test]
+    fn rejects_malformed_chords() {
+        let error = |accelerator: &str| Keys::parse(accelerator).err().unwrap();
+
+        assert!(error("Space H").contains("needs a modifier"));
+        assert!(error("Ctrl+K Ctrl+H Ctrl+J").contains("Invalid chord"));
+        assert_eq!(error("   "), "Empty shortcut");
+        assert!(error("Ctrl+K Nope").starts_with("Invalid shortcut"));
+    }
+
+    #[test]
+    fn plain_shortcuts_clash_on_the_same_keys() {
+        assert!(keys("Ctrl+Shift+A").clashes(&keys("Shift+Ctrl+A")));
+        assert!(!keys("Ctrl+Shift+A").clashes(&keys("Ctrl+Alt+A")));
+    }
+
+    #[test]
+    fn a_leader_clashes_with_a_plain_shortcut_on_the_same_keys() {
+        assert!(keys("Ctrl+Shift+Space").clashes(&keys("Ctrl+Shift+Space H")));
+        assert!(keys("Ctrl+Shift+Space H").clashes(&keys("Ctrl+Shift+Space")));
+    }
+
+    #[test]
+    fn chords_clash_only_on_the_same_follow_up() {
+        assert!(keys("Ctrl+Shift+Space H").clashes(&keys("Ctrl+Shift+Space H")));
+        assert!(!keys("Ctrl+Shift+Space H").clashes(&keys("Ctrl+Shift+Space L")));
+        assert!(!keys("Ctrl+Shift+Space H").clashes(&keys("Ctrl+Alt+Space H")));
+    }
+
+    #[test]
+    fn plan_register_asks_the_os_for_a_new_leader() {
+        let entries = [registered("Ctrl+Shift+W", ShortcutAction::ToggleMain)];
+        let mut entry = Entry::new(binding("Ctrl+Shift+Space H", go("/home")));
+
+        let leader = plan_register(&entries, &mut entry).unwrap();
+        assert_eq!(leader.id(), shortcut("Ctrl+Shift+Space").id());
+        assert!(matches!(entry.status, RegistrationStatus::Pending));
+    }
+
+    #[test]
+    fn plan_register_shares_a_leader_between_chords() {
+        let entries = [registered("Ctrl+Shift+Space H", go("/home"))];
+        let mut entry = Entry::new(binding("Ctrl+Shift+Space L", go("/library/all")));
+
+        assert!(plan_register(&entries, &mut entry).is_none());
+        assert!(entry.is_registered());
+    }
+
+    #[test]
+    fn plan_register_reports_conflicts_with_live_entries() {
+        let entries = [registered("Ctrl+Shift+Space H", go("/home"))];
+
+        let mut same_chord = Entry::new(binding("Ctrl+Shift+Space H", go("/movies")));
+        assert!(plan_register(&entries, &mut same_chord).is_none());
+        assert!(matches!(
+            &same_chord.status,
+            RegistrationStatus::Conflict { action } if *action == go("/home")
+        ));
+
+        let mut plain_leader = Entry::new(binding("Ctrl+Shift+Space", ShortcutAction::QuickAdd));
+        assert!(plan_register(&entries, &mut plain_leader).is_none());
+        assert!(matches!(plain_leader.status, RegistrationStatus::Conflict { .. }));
+    }
+
+    #[test]
+    fn plan_register_ignores_entries_that_are_not_live() {
+        // A failed entry holds nothing, so the same keys are free
+        let mut failed = Entry::new(binding("Ctrl+Shift+A", ShortcutAction::QuickAdd));
+        failed.status = RegistrationStatus::Failed {
+            message: "taken".to_string(),
+        };
+        let mut entry = Entry::new(binding("Ctrl+Shift+A", ShortcutAction::SyncNow));
+        assert!(plan_register(&[failed], &mut entry).is_some());
+
+        // Only pending entries are planned
+        let mut invalid = Entry::new(binding("Ctrl+Shift+Nope", ShortcutAction::SyncNow));
+        assert!(plan_register(&[], &mut invalid).is_none());
+        assert!(failure(&invalid).is_some());
+    }
+
+    #[test]
+    fn plan_unregister_keeps_a_leader_other_chords_use() {
+        let others = [registered("Ctrl+Shift+Space L", go("/library/all"))];
+        let mut entry = registered("Ctrl+Shift+Space H", go("/home"));
+        assert!(plan_unregister(&others, &mut entry).is_none());
+        assert!(matches!(entry.status, RegistrationStatus::Pending));
 
-    Ok(app.global_shortcut().is_registered(shortcut_obj))
+        let mut entry = registered("Ctrl+Shift+Space H", go("/home"));
+        let released = plan_unregister(&[], &mut entry).unwrap();
+        assert_eq!(released.id(), shortcut("Ctrl+Shift+Space").id());
+    }
 }
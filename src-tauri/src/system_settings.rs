@@ -1,28 +1,290 @@
-use tauri::{AppHandle, Manager, State};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-#[derive(Default)]
+use crate::error::{AppError, AppResult};
+
+const SETTINGS_FILE: &str = "system-settings.json";
+
+/// Bump when the shape changes and add a step to `migrate`
+const SETTINGS_VERSION: u32 = 1;
+
+/// Native settings that have to be known before the webview loads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct SystemSettings {
-    pub keep_running_in_background: Mutex<bool>,
+    pub launch_on_startup: bool,
+    /// Keep the window hidden when the app is started by autostart
+    pub start_minimized: bool,
+    /// The app lives on in the tray once no window is showing; when off, closing quits
+    pub keep_running_in_background: bool,
+    /// Closing the main window hides it to the tray
+    pub close_to_tray: bool,
+    /// Minimizing the main window hides it to the tray
+    pub minimize_to_tray: bool,
+}
+
+impl Default for SystemSettings {
+    fn default() -> Self {
+        Self {
+            launch_on_startup: false,
+            start_minimized: false,
+            keep_running_in_background: true,
+            close_to_tray: true,
+            minimize_to_tray: false,
+        }
+    }
+}
+
+/// Fields to change; anything left out keeps its current value
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemSettingsPatch {
+    pub launch_on_startup: Option<bool>,
+    pub start_minimized: Option<bool>,
+    pub keep_running_in_background: Option<bool>,
+    pub close_to_tray: Option<bool>,
+    pub minimize_to_tray: Option<bool>,
+}
+
+impl SystemSettings {
+    fn apply(&mut self, patch: SystemSettingsPatch) {
+        if let Some(value) = patch.launch_on_startup {
+            self.launch_on_startup = value;
+        }
+        if let Some(value) = patch.start_minimized {
+            self.start_minimized = value;
+        }
+        if let Some(value) = patch.keep_running_in_background {
+            self.keep_running_in_background = value;
+        }
+        if let Some(value) = patch.close_to_tray {
+            self.close_to_tray = value;
+        }
+        if let Some(value) = patch.minimize_to_tray {
+            self.minimize_to_tray = value;
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SettingsFile<'a> {
+    version: u32,
+    #[serde(flatten)]
+    settings: &'a SystemSettings,
+}
+
+/// Bring a settings file of any older version up to `SETTINGS_VERSION`
+fn migrate(mut value: Value) -> Result<SystemSettings, String> {
+    let object = value.as_object_mut().ok_or("Settings file is not an object")?;
+    // Every file written has a version; one edited by hand without it is read as current
+    let version = object
+        .remove("version")
+        .and_then(|v| v.as_u64())
+        .map_or(SETTINGS_VERSION, |v| v as u32);
+    if version > SETTINGS_VERSION {
+        return Err(format!("Settings file has unknown version {}", version));
+    }
+
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// System settings, persisted in the app config dir
+pub struct SystemSettingsStore {
+    path: Option<PathBuf>,
+    settings: Mutex<SystemSettings>,
+}
+
+impl SystemSettingsStore {
+    /// Read the settings file, falling back to defaults when it is missing or unreadable
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(SETTINGS_FILE));
+
+        let settings = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => serde_json::from_str(&content)
+                .map_err(|e| e.to_string())
+                .and_then(migrate)
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to read system settings, using defaults: {}", e);
+                    SystemSettings::default()
+                }),
+            _ => SystemSettings::default(),
+        };
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> AppResult<SystemSettings> {
+        Ok(self.settings.lock()?.clone())
+    }
+
+    fn save(&self, settings: &SystemSettings) -> AppResult<()> {
+        let Some(path) = &self.path else {
+            return Err(AppError::unavailable("App config directory is not available"));
+        };
+        let file = SettingsFile {
+            version: SETTINGS_VERSION,
+            settings,
+        };
+        let content = serde_json::to_string_pretty(&file)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, content)?;
+        Ok(std::fs::rename(&temp, path)?)
+    }
+
+    /// Apply, persist and broadcast `system-settings:changed` to every window
+    pub fn update(&self, app: &AppHandle, patch: SystemSettingsPatch) -> AppResult<SystemSettings> {
+        let updated = {
+            let mut settings = self.settings.lock()?;
+            let mut updated = settings.clone();
+            updated.apply(patch);
+            if updated == *settings {
+                return Ok(updated);
+            }
+            self.save(&updated)?;
+            *settings = updated.clone();
+            updated
+        };
+
+        let _ = app.emit("system-settings:changed", &updated);
+        Ok(updated)
+    }
+}
+
+/// Current settings, or the defaults before the store is managed
+pub fn current(app: &AppHandle) -> SystemSettings {
+    app.try_state::<SystemSettingsStore>()
+        .and_then(|store| store.get().ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_system_settings(store: State<SystemSettingsStore>) -> AppResult<SystemSettings> {
+    store.get()
+}
+
+#[tauri::command]
+pub fn update_system_settings(
+    app: AppHandle,
+    store: State<SystemSettingsStore>,
+    patch: SystemSettingsPatch,
+) -> AppResult<SystemSettings> {
+    store.update(&app, patch)
 }
 
 #[tauri::command]
 pub fn set_keep_running_in_background(
-    settings: State<SystemSettings>,
+    app: AppHandle,
+    store: State<SystemSettingsStore>,
     enabled: bool,
-) -> Result<(), String> {
-    *settings.keep_running_in_background.lock().unwrap() = enabled;
-    Ok(())
+) -> AppResult<()> {
+    store
+        .update(
+            &app,
+            SystemSettingsPatch {
+                keep_running_in_background: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .map(|_| ())
 }
 
 #[tauri::command]
-pub fn get_keep_running_in_background(settings: State<SystemSettings>) -> bool {
-    *settings.keep_running_in_background.lock().unwrap()
+pub fn get_keep_running_in_background(store: State<SystemSettingsStore>) -> AppResult<bool> {
+    store.get().map(|settings| settings.keep_running_in_background)
 }
 
+/// Whether closing the main window should hide it instead of quitting
 pub fn should_keep_running(app: &AppHandle) -> bool {
-    match app.try_state::<SystemSettings>() {
-        Some(settings) => *settings.keep_running_in_background.lock().unwrap(),
-        None => true, // Default to true
+    let settings = current(app);
+    settings.keep_running_in_background && settings.close_to_tray
+}
+
+/// Whether minimizing the main window should hide it to the tray
+pub fn should_minimize_to_tray(app: &AppHandle) -> bool {
+    let settings = current(app);
+    settings.keep_running_in_background && settings.minimize_to_tray
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_current_version() {
+        let settings = migrate(json!({
+            "version": SETTINGS_VERSION,
+            "launchOnStartup": true,
+            "startMinimized": true,
+            "keepRunningInBackground": false,
+            "closeToTray": false,
+            "minimizeToTray": true,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            settings,
+            SystemSettings {
+                launch_on_startup: true,
+                start_minimized: true,
+                keep_running_in_background: false,
+                close_to_tray: false,
+                minimize_to_tray: true,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_fields_take_their_defaults() {
+        let settings = migrate(json!({ "version": SETTINGS_VERSION, "startMinimized": true })).unwrap();
+        assert_eq!(
+            settings,
+            SystemSettings {
+                start_minimized: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(migrate(json!({})).unwrap(), SystemSettings::default());
+    }
+
+    #[test]
+    fn rejects_an_unknown_future_version() {
+        let error = migrate(json!({ "version": SETTINGS_VERSION + 1, "startMinimized": true })).unwrap_err();
+        assert_eq!(error, format!("Settings file has unknown version {}", SETTINGS_VERSION + 1));
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(migrate(json!([true])).is_err());
+        assert!(migrate(json!({ "version": SETTINGS_VERSION, "startMinimized": "yes" })).is_err());
+    }
+
+    #[test]
+    fn written_files_read_back() {
+        let settings = SystemSettings {
+            minimize_to_tray: true,
+            ..Default::default()
+        };
+        let file = serde_json::to_value(SettingsFile {
+            version: SETTINGS_VERSION,
+            settings: &settings,
+        })
+        .unwrap();
+        assert_eq!(file["version"], json!(SETTINGS_VERSION));
+        assert_eq!(migrate(file).unwrap(), settings);
     }
 }
@@ -1,4 +1,65 @@
-use tauri::{AppHandle, Manager, Emitter, tray::*, menu::*};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Emitter, Listener, Wry, image::Image, tray::*, menu::*};
+
+use crate::error::{AppError, AppResult};
+use crate::import::parse_date;
+use crate::library::{CurrentMedia, LibraryQuery, LibrarySort, LibraryStore, MediaRef};
+use crate::media::{self, LibraryMedia, WatchStatus};
+
+/// Quick Status menu ids and the status each one applies
+const QUICK_STATUSES: [(&str, &str, WatchStatus); 5] = [
+    ("mark_watching", "Watching", WatchStatus::Watching),
+    ("mark_completed", "Completed", WatchStatus::Completed),
+    ("mark_plan", "Plan to Watch", WatchStatus::WillWatch),
+    ("mark_on_hold", "On Hold", WatchStatus::OnHold),
+    ("mark_dropped", "Dropped", WatchStatus::Dropped),
+];
+
+/// Handles to the Quick Status submenu so it can follow the current title
+pub struct QuickStatusMenu {
+    title: MenuItem<Wry>,
+    statuses: Vec<(WatchStatus, CheckMenuItem<Wry>)>,
+}
+
+/// How many titles each library section of the tray shows
+const SECTION_SIZE: u32 = 5;
+
+/// How long library changes are gathered before the sections are rebuilt once for all of them
+const SECTION_REFRESH_DELAY: Duration = Duration::from_millis(250);
+
+/// Prefix of the menu ids of per-title actions: `library_item:<section>:<action>:<id>`
+const LIBRARY_ITEM_PREFIX: &str = "library_item:";
+
+/// The tray submenus rebuilt from library state
+pub struct LibrarySections {
+    continue_watching: Submenu<Wry>,
+    recently_added: Submenu<Wry>,
+    /// Set while a rebuild is scheduled, so the changes a sync or import makes share it
+    refresh_scheduled: AtomicBool,
+}
+
+/// Sync health reported by the frontend's sync engine
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum SyncState {
+    #[serde(rename_all = "camelCase")]
+    Idle { last_synced_at: Option<String> },
+    Syncing { pending: u32 },
+    Offline,
+    Error { message: Option<String> },
+}
+
+/// The tray icon and the pieces of it that change at runtime
+pub struct TrayState {
+    tray: TrayIcon<Wry>,
+    base_icon: Image<'static>,
+    last_synced: MenuItem<Wry>,
+    sync: Mutex<(SyncState, Option<DateTime<Utc>>)>,
+}
 
 pub fn create_tray(app: &AppHandle) -> Result<(), tauri::Error> {
     // Create tray menu
@@ -16,25 +77,39 @@ pub fn create_tray(app: &AppHandle) -> Result<(), tauri::Error> {
     let statistics = MenuItemBuilder::with_id("statistics", "Statistics").build(app)?;
     let separator3 = PredefinedMenuItem::separator(app)?;
 
-    // Status actions submenu
-    let mark_watching = MenuItemBuilder::with_id("mark_watching", "Watching").build(app)?;
-    let mark_completed = MenuItemBuilder::with_id("mark_completed", "Completed").build(app)?;
-    let mark_plan = MenuItemBuilder::with_id("mark_plan", "Plan to Watch").build(app)?;
-    let mark_on_hold = MenuItemBuilder::with_id("mark_on_hold", "On Hold").build(app)?;
-    let mark_dropped = MenuItemBuilder::with_id("mark_dropped", "Dropped").build(app)?;
-
-    let quick_status = SubmenuBuilder::new(app, "Quick Status")
-        .item(&mark_watching)
-        .item(&mark_completed)
-        .item(&mark_plan)
-        .item(&mark_on_hold)
-        .item(&mark_dropped)
-        .build()?;
+    // Library sections, filled in by refresh_library_sections
+    let continue_watching = SubmenuBuilder::new(app, "Continue Watching").build()?;
+    let recently_added = SubmenuBuilder::new(app, "Recently Added").build()?;
+
+    // Status actions submenu, applied to the current title
+    let current_title = MenuItemBuilder::with_id("current_title", "No title selected")
+        .enabled(false)
+        .build(app)?;
+    let mut statuses = Vec::new();
+    let mut quick_status = SubmenuBuilder::new(app, "Quick Status")
+        .item(&current_title)
+        .separator();
+    for (id, label, status) in QUICK_STATUSES {
+        let item = CheckMenuItemBuilder::with_id(id, label)
+            .enabled(false)
+            .build(app)?;
+        quick_status = quick_status.item(&item);
+        statuses.push((status, item));
+    }
+    let quick_status = quick_status.build()?;
+
+    app.manage(QuickStatusMenu {
+        title: current_title,
+        statuses,
+    });
 
     let separator4 = PredefinedMenuItem::separator(app)?;
 
     // Sync status
     let sync_now = MenuItemBuilder::with_id("sync_now", "Sync Now").build(app)?;
+    let last_synced = MenuItemBuilder::with_id("last_synced", "Not synced yet")
+        .enabled(false)
+        .build(app)?;
     let separator5 = PredefinedMenuItem::separator(app)?;
 
     // Settings and quit
@@ -53,19 +128,27 @@ pub fn create_tray(app: &AppHandle) -> Result<(), tauri::Error> {
         .item(&discover)
         .item(&statistics)
         .item(&separator3)
+        .item(&continue_watching)
+        .item(&recently_added)
         .item(&quick_status)
         .item(&separator4)
         .item(&sync_now)
+        .item(&last_synced)
         .item(&separator5)
         .item(&preferences)
         .item(&separator6)
         .item(&quit)
         .build()?;
 
+    let icon = app.default_window_icon().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "No default window icon for the tray")
+    })?;
+    let base_icon = Image::new_owned(icon.rgba().to_vec(), icon.width(), icon.height());
+
     // Create the tray with icon
-    let _tray = TrayIconBuilder::new()
+    let tray = TrayIconBuilder::with_id("main")
         .menu(&menu)
-        .icon(app.default_window_icon().unwrap().clone())
+        .icon(base_icon.clone())
         .tooltip("Watchfolio")
         .on_menu_event(move |app, event| handle_tray_event(app, event))
         .on_tray_icon_event(|tray, event| {
@@ -89,9 +172,256 @@ pub fn create_tray(app: &AppHandle) -> Result<(), tauri::Error> {
         })
         .build(app)?;
 
+    app.manage(LibrarySections {
+        continue_watching,
+        recently_added,
+        refresh_scheduled: AtomicBool::new(false),
+    });
+    app.manage(TrayState {
+        tray,
+        base_icon,
+        last_synced,
+        sync: Mutex::new((SyncState::Idle { last_synced_at: None }, None)),
+    });
+
+    // "Last synced 3 min ago" goes stale on its own, so refresh it every minute
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            refresh_last_synced(&app_handle);
+        }
+    });
+
+    // Keep Quick Status in step with the library and whatever the main window shows
+    for event in ["library:changed", "library:current-changed"] {
+        let app_handle = app.clone();
+        app.listen(event, move |_| refresh_quick_status(&app_handle));
+    }
+    refresh_quick_status(app);
+
+    let app_handle = app.clone();
+    app.listen("library:changed", move |_| schedule_library_sections_refresh(&app_handle));
+    refresh_library_sections(app);
+
     Ok(())
 }
 
+/// Point the Quick Status submenu at the current title, or disable it when there is none
+pub fn refresh_quick_status(app: &AppHandle) {
+    let Some(menu) = app.try_state::<QuickStatusMenu>() else {
+        return;
+    };
+    let current = app.try_state::<CurrentMedia>().and_then(|current| current.get());
+    let status = current.as_ref().and_then(|media| {
+        app.try_state::<LibraryStore>()
+            .and_then(|store| store.find(media.media_type, media.tmdb_id).ok().flatten())
+            .map(|item| item.status)
+    });
+
+    let _ = menu
+        .title
+        .set_text(current.as_ref().map_or("No title selected", |media| media.title.as_str()));
+    for (item_status, item) in &menu.statuses {
+        let _ = item.set_enabled(current.is_some());
+        let _ = item.set_checked(status == Some(*item_status));
+    }
+}
+
+/// Rebuild the library sections shortly, once for however many changes arrive in the meantime
+fn schedule_library_sections_refresh(app: &AppHandle) {
+    let Some(sections) = app.try_state::<LibrarySections>() else {
+        return;
+    };
+    if sections.refresh_scheduled.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SECTION_REFRESH_DELAY).await;
+        // Cleared first, so a change made during the rebuild schedules another
+        app.state::<LibrarySections>().refresh_scheduled.store(false, Ordering::Release);
+        refresh_library_sections(&app);
+    });
+}
+
+/// Rebuild "Continue Watching" and "Recently Added" from the library
+pub fn refresh_library_sections(app: &AppHandle) {
+    let (Some(sections), Some(store)) = (
+        app.try_state::<LibrarySections>(),
+        app.try_state::<LibraryStore>(),
+    ) else {
+        return;
+    };
+
+    let watching = store.list(&LibraryQuery {
+        status: Some(WatchStatus::Watching),
+        sort: LibrarySort::LastUpdatedAt,
+        limit: Some(SECTION_SIZE),
+        ..Default::default()
+    });
+    let added = store.list(&LibraryQuery {
+        sort: LibrarySort::AddedAt,
+        limit: Some(SECTION_SIZE),
+        ..Default::default()
+    });
+
+    let filled = fill_section(app, &sections.continue_watching, "watching", watching, "Nothing in progress")
+        .and_then(|_| fill_section(app, &sections.recently_added, "added", added, "Your library is empty"));
+    if let Err(e) = filled {
+        log::error!("Failed to rebuild tray library sections: {}", e);
+    }
+}
+
+fn fill_section(
+    app: &AppHandle,
+    section: &Submenu<Wry>,
+    name: &str,
+    items: AppResult<Vec<LibraryMedia>>,
+    empty_label: &str,
+) -> Result<(), tauri::Error> {
+    while section.remove_at(0)?.is_some() {}
+
+    let items = items.unwrap_or_else(|e| {
+        log::error!("Failed to read library for the tray: {}", e);
+        Vec::new()
+    });
+    if items.is_empty() {
+        let empty = MenuItemBuilder::new(empty_label).enabled(false).build(app)?;
+        return section.append(&empty);
+    }
+
+    for item in &items {
+        section.append(&library_item_menu(app, name, item)?)?;
+    }
+    Ok(())
+}
+
+/// Per-title submenu: mark completed, open the details page, rate
+fn library_item_menu(app: &AppHandle, section: &str, item: &LibraryMedia) -> Result<Submenu<Wry>, tauri::Error> {
+    let id = |action: &str| format!("{}{}:{}:{}", LIBRARY_ITEM_PREFIX, section, action, item.id);
+
+    let complete = MenuItemBuilder::with_id(id("complete"), "Mark as Completed")
+        .enabled(item.status != WatchStatus::Completed)
+        .build(app)?;
+    let open = MenuItemBuilder::with_id(id("open"), "Open Details").build(app)?;
+
+    let current_rating = item.user_rating.map(|rating| rating.round() as u8);
+    let mut rate = SubmenuBuilder::new(app, "Rate");
+    for rating in (1..=10u8).rev() {
+        let choice = CheckMenuItemBuilder::with_id(id(&format!("rate_{}", rating)), format!("{}/10", rating))
+            .checked(current_rating == Some(rating))
+            .build(app)?;
+        rate = rate.item(&choice);
+    }
+    let clear = MenuItemBuilder::with_id(id("unrate"), "Clear Rating")
+        .enabled(item.user_rating.is_some())
+        .build(app)?;
+    let rate = rate.separator().item(&clear).build()?;
+
+    SubmenuBuilder::new(app, &item.title)
+        .item(&complete)
+        .item(&open)
+        .item(&rate)
+        .build()
+}
+
+/// Run a per-title action picked from one of the library sections
+fn handle_library_item_event(app: &AppHandle, event_id: &str) {
+    let Some((action, id)) = event_id
+        .strip_prefix(LIBRARY_ITEM_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(_section, rest)| rest.split_once(':'))
+    else {
+        return;
+    };
+    let Some(store) = app.try_state::<LibraryStore>() else {
+        return;
+    };
+    let item = match store.get(id) {
+        Ok(Some(item)) => item,
+        Ok(None) => return,
+        Err(e) => {
+            log::error!("Failed to read library item {}: {}", id, e);
+            return;
+        }
+    };
+
+    let updated = match action {
+        "open" => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+                let _ = window.emit(
+                    "tray:navigate",
+                    media::details_path(item.media_type, item.tmdb_id, &item.title),
+                );
+            }
+            return;
+        }
+        "complete" => store
+            .set_status(&MediaRef::from(&item), WatchStatus::Completed)
+            .map(|item| (item, "Marked as Completed".to_string())),
+        "unrate" => store
+            .set_rating(id, None)
+            .map(|item| (item, "Rating cleared".to_string())),
+        action => {
+            let Some(rating) = action.strip_prefix("rate_").and_then(|r| r.parse::<u8>().ok()) else {
+                return;
+            };
+            store
+                .set_rating(id, Some(f64::from(rating)))
+                .map(|item| (item, format!("Rated {}/10", rating)))
+        }
+    };
+
+    match updated {
+        Ok((item, message)) => {
+            crate::library::notify_change(
+                app,
+                crate::library::LibraryChangeKind::Upserted,
+                vec![item.id],
+            );
+            notify(app, &item.title, &message);
+        }
+        Err(e) => {
+            log::error!("Failed to update {}: {}", item.title, e);
+            notify(app, "Couldn't update library", &item.title);
+        }
+    }
+}
+
+/// Apply a Quick Status choice to the current title and confirm it with a notification
+pub fn mark_current(app: &AppHandle, status: WatchStatus) {
+    let label = QUICK_STATUSES
+        .iter()
+        .find(|(.., item_status)| *item_status == status)
+        .map_or(status.as_str(), |(_, label, _)| *label);
+    let Some(media) = app.try_state::<CurrentMedia>().and_then(|current| current.get()) else {
+        return;
+    };
+    let Some(store) = app.try_state::<LibraryStore>() else {
+        return;
+    };
+
+    match store.set_status(&media, status) {
+        Ok(item) => {
+            crate::library::notify_change(
+                app,
+                crate::library::LibraryChangeKind::Upserted,
+                vec![item.id],
+            );
+            notify(app, "Status updated", &format!("{} marked as {}", media.title, label));
+        }
+        Err(e) => {
+            log::error!("Failed to update status of {}: {}", media.title, e);
+            notify(app, "Couldn't update status", &media.title);
+        }
+    }
+}
+
 pub fn handle_tray_event(app: &AppHandle, event: MenuEvent) {
     let window = app.get_webview_window("main");
 
@@ -139,39 +469,10 @@ pub fn handle_tray_event(app: &AppHandle, event: MenuEvent) {
                 let _ = window.emit("tray:statistics", ());
             }
         }
-        "mark_watching" => {
-            if let Some(window) = window {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.emit("tray:navigate", "/library/watching");
-            }
-        }
-        "mark_completed" => {
-            if let Some(window) = window {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.emit("tray:navigate", "/library/completed");
-            }
-        }
-        "mark_plan" => {
-            if let Some(window) = window {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.emit("tray:navigate", "/library/will-watch");
-            }
-        }
-        "mark_on_hold" => {
-            if let Some(window) = window {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.emit("tray:navigate", "/library/on-hold");
-            }
-        }
-        "mark_dropped" => {
-            if let Some(window) = window {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.emit("tray:navigate", "/library/dropped");
+        id if id.starts_with(LIBRARY_ITEM_PREFIX) => handle_library_item_event(app, id),
+        id if id.starts_with("mark_") => {
+            if let Some((.., status)) = QUICK_STATUSES.iter().find(|(item_id, ..)| *item_id == id) {
+                mark_current(app, *status);
             }
         }
         "sync_now" => {
@@ -193,16 +494,127 @@ pub fn handle_tray_event(app: &AppHandle, event: MenuEvent) {
     }
 }
 
+/// Badge colour drawn over the tray icon for each sync state; idle shows the plain icon
+fn badge_color(state: &SyncState) -> Option<[u8; 4]> {
+    match state {
+        SyncState::Idle { .. } => None,
+        SyncState::Syncing { .. } => Some([59, 130, 246, 255]),
+        SyncState::Offline => Some([156, 163, 175, 255]),
+        SyncState::Error { .. } => Some([239, 68, 68, 255]),
+    }
+}
+
+/// Draw a status dot in the bottom-right corner of the icon
+fn badged_icon(base: &Image<'_>, color: [u8; 4]) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f32 * 0.22;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
+
+fn tooltip_for(state: &SyncState) -> String {
+    match state {
+        SyncState::Idle { .. } => "Watchfolio".to_string(),
+        SyncState::Syncing { pending: 0 } => "Watchfolio - Syncing".to_string(),
+        SyncState::Syncing { pending: 1 } => "Watchfolio - Syncing 1 item".to_string(),
+        SyncState::Syncing { pending } => format!("Watchfolio - Syncing {} items", pending),
+        SyncState::Offline => "Watchfolio - Offline".to_string(),
+        SyncState::Error { message: Some(message) } => format!("Watchfolio - Sync error: {}", message),
+        SyncState::Error { message: None } => "Watchfolio - Sync error".to_string(),
+    }
+}
+
+fn format_last_synced(last_synced_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+    let Some(last_synced_at) = last_synced_at else {
+        return "Not synced yet".to_string();
+    };
+    let minutes = (now - last_synced_at).num_minutes().max(0);
+    match minutes {
+        0 => "Last synced just now".to_string(),
+        1..=59 => format!("Last synced {} min ago", minutes),
+        60..=1439 => format!("Last synced {} h ago", minutes / 60),
+        _ => format!("Last synced {} d ago", minutes / 1440),
+    }
+}
+
+fn refresh_last_synced(app: &AppHandle) {
+    let Some(tray_state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let Ok(sync) = tray_state.sync.lock() else {
+        return;
+    };
+    let text = match &sync.0 {
+        SyncState::Syncing { .. } => "Syncing…".to_string(),
+        _ => format_last_synced(sync.1, Utc::now()),
+    };
+    let _ = tray_state.last_synced.set_text(text);
+}
+
 // Update tray menu dynamically
 #[tauri::command]
-pub async fn update_tray_tooltip(_app: AppHandle, _tooltip: String) -> Result<(), String> {
-    // This would update the tray tooltip with current status
-    // e.g., "Watchfolio - 5 items syncing"
+pub async fn update_tray_tooltip(app: AppHandle, tooltip: String) -> AppResult<()> {
+    let tray_state = app
+        .try_state::<TrayState>()
+        .ok_or_else(|| AppError::unavailable("Tray is not available"))?;
+    Ok(tray_state.tray.set_tooltip(Some(tooltip))?)
+}
+
+/// Reflect the sync engine's state in the tray icon, tooltip and menu
+#[tauri::command]
+pub async fn set_sync_state(app: AppHandle, state: SyncState) -> AppResult<()> {
+    let tray_state = app
+        .try_state::<TrayState>()
+        .ok_or_else(|| AppError::unavailable("Tray is not available"))?;
+
+    {
+        let mut sync = tray_state.sync.lock()?;
+        if let SyncState::Idle { last_synced_at } = &state {
+            let reported = last_synced_at
+                .as_ref()
+                .and_then(|date| parse_date(&serde_json::Value::String(date.clone())));
+            // Finishing a sync counts as a sync even if the frontend didn't say when
+            let finished_now = matches!(sync.0, SyncState::Syncing { .. }).then(Utc::now);
+            sync.1 = reported.or(finished_now).or(sync.1);
+        }
+        sync.0 = state.clone();
+    }
+
+    let icon = match badge_color(&state) {
+        Some(color) => badged_icon(&tray_state.base_icon, color),
+        None => tray_state.base_icon.clone(),
+    };
+    tray_state.tray.set_icon(Some(icon))?;
+    tray_state.tray.set_tooltip(Some(tooltip_for(&state)))?;
+    refresh_last_synced(&app);
+
     Ok(())
 }
 
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        use tauri_plugin_notification::NotificationExt;
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            log::warn!("Failed to show notification: {}", e);
+        }
+    }
+}
+
 #[tauri::command]
-pub async fn show_tray_notification(app: AppHandle, title: String, body: String) -> Result<(), String> {
+pub async fn show_tray_notification(app: AppHandle, title: String, body: String) -> AppResult<()> {
     // Show notification from tray
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     {
@@ -212,7 +624,7 @@ pub async fn show_tray_notification(app: AppHandle, title: String, body: String)
             .title(title)
             .body(body)
             .show()
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| AppError::internal(e.to_string()))?;
     }
     Ok(())
 }
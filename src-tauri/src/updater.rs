@@ -1,52 +1,325 @@
-use tauri::{AppHandle, Emitter};
-use tauri_plugin_updater::UpdaterExt;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tokio::sync::{oneshot, Notify};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+
+const UPDATER_FILE: &str = "updater.json";
+/// Downloaded updates waiting to be installed, under the app cache dir
+const STAGING_DIR: &str = "updates";
+
+const DEFAULT_CHECK_INTERVAL_HOURS: u32 = 24;
+/// Longest interval the settings accept, 30 days
+const MAX_CHECK_INTERVAL_HOURS: u32 = 720;
+/// First retry after a failed scheduled check; doubles with each further failure
+const RETRY_BASE: Duration = Duration::from_secs(5 * 60);
+
+const RELEASES_URL: &str = "https://github.com/zawalid/watchfolio/releases";
 
 #[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 struct UpdateProgress {
     downloaded: usize,
     content_length: Option<u64>,
 }
 
 #[derive(Clone, serde::Serialize)]
-struct UpdateAvailable {
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAvailable {
     version: String,
     current_version: String,
     date: Option<String>,
     body: Option<String>,
+    channel: UpdateChannel,
+    /// The release is older than the running build, only offered after switching to a more stable channel
+    downgrade: bool,
+}
+
+/// Returned by `set_update_channel`
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelSwitch {
+    channel: UpdateChannel,
+    /// The release to confirm when the new channel is behind the running build
+    downgrade: Option<UpdateAvailable>,
+}
+
+/// Release channel to take updates from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl UpdateChannel {
+    /// Manifest for the channel; beta and nightly builds are published under rolling tags
+    fn endpoint(&self) -> String {
+        match self {
+            UpdateChannel::Stable => format!("{}/latest/download/latest.json", RELEASES_URL),
+            UpdateChannel::Beta => format!("{}/download/beta/latest.json", RELEASES_URL),
+            UpdateChannel::Nightly => format!("{}/download/nightly/latest.json", RELEASES_URL),
+        }
+    }
+}
+
+/// Updater preferences and state, persisted in the app config dir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdaterSettings {
+    pub channel: UpdateChannel,
+    /// Check in the background; manual checks work either way
+    pub auto_check: bool,
+    pub check_interval_hours: u32,
+    /// RFC 3339 time of the last successful check
+    pub last_check_at: Option<String>,
+    /// Versions the user chose to skip; forgotten once a newer release appears
+    pub skipped_versions: Vec<String>,
+    /// RFC 3339 time before which background checks keep quiet
+    pub remind_after: Option<String>,
+}
+
+impl Default for UpdaterSettings {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::default(),
+            auto_check: true,
+            check_interval_hours: DEFAULT_CHECK_INTERVAL_HOURS,
+            last_check_at: None,
+            skipped_versions: Vec::new(),
+            remind_after: None,
+        }
+    }
+}
+
+impl UpdaterSettings {
+    fn interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.check_interval_hours.clamp(1, MAX_CHECK_INTERVAL_HOURS)) * 3600)
+    }
+
+    /// Time until the next scheduled check is due, zero when overdue
+    fn until_due(&self, now: DateTime<Utc>) -> Duration {
+        let Some(last_check_at) = self
+            .last_check_at
+            .as_deref()
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        else {
+            return Duration::ZERO;
+        };
+        let elapsed = (now - last_check_at.with_timezone(&Utc)).to_std().unwrap_or(Duration::ZERO);
+        self.interval().saturating_sub(elapsed)
+    }
+
+    /// Time to wait before the next scheduled check after `failures` failed ones in a row,
+    /// `None` while background checks are off
+    fn next_delay(&self, failures: u32, now: DateTime<Utc>) -> Option<Duration> {
+        if !self.auto_check {
+            return None;
+        }
+        if failures > 0 {
+            return Some(retry_backoff(failures).min(self.interval()));
+        }
+        Some(self.until_due(now))
+    }
+
+    /// Whether background checks should stay quiet about `version`
+    fn is_deferred(&self, version: &str, now: DateTime<Utc>) -> bool {
+        let reminding_later = self
+            .remind_after
+            .as_deref()
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .is_some_and(|remind_after| remind_after > now);
+        reminding_later || self.skipped_versions.iter().any(|skipped| skipped == version)
+    }
+
+    /// A release newer than every skipped version makes the skip list moot
+    fn supersedes_skipped(&self, version: &str) -> bool {
+        let Some(version) = parse_version(version) else {
+            return false;
+        };
+        !self.skipped_versions.is_empty()
+            && self
+                .skipped_versions
+                .iter()
+                .filter_map(|skipped| parse_version(skipped))
+                .all(|skipped| version > skipped)
+    }
+}
+
+/// `RETRY_BASE` doubled for every failure after the first
+fn retry_backoff(failures: u32) -> Duration {
+    RETRY_BASE.saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+}
+
+fn parse_version(version: &str) -> Option<semver::Version> {
+    semver::Version::parse(version.trim_start_matches('v')).ok()
+}
+
+pub struct UpdaterStore {
+    path: Option<PathBuf>,
+    settings: Mutex<UpdaterSettings>,
+}
+
+impl UpdaterStore {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(UPDATER_FILE));
+
+        let settings = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Failed to read updater settings, using defaults: {}", e);
+                UpdaterSettings::default()
+            }),
+            _ => UpdaterSettings::default(),
+        };
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> AppResult<UpdaterSettings> {
+        Ok(self.settings.lock()?.clone())
+    }
+
+    /// Change the settings and write them to disk
+    pub fn update(&self, change: impl FnOnce(&mut UpdaterSettings)) -> AppResult<UpdaterSettings> {
+        let mut settings = self.settings.lock()?;
+        let mut updated = settings.clone();
+        change(&mut updated);
+
+        let Some(path) = &self.path else {
+            return Err(AppError::unavailable("App config directory is not available"));
+        };
+        let content = serde_json::to_string_pretty(&updated)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, path)?;
+
+        *settings = updated.clone();
+        Ok(updated)
+    }
+}
+
+fn channel(app: &AppHandle) -> UpdateChannel {
+    app.try_state::<UpdaterStore>()
+        .and_then(|store| store.get().ok())
+        .map(|settings| settings.channel)
+        .unwrap_or_default()
+}
+
+/// Check the selected channel. Older releases are only reported when `allow_downgrade` is set,
+/// so switching from beta back to stable never downgrades on its own.
+async fn check_channel(
+    app: &AppHandle,
+    allow_downgrade: bool,
+) -> Result<Option<Update>, Box<dyn std::error::Error>> {
+    let channel = channel(app);
+    let endpoint = Url::parse(&channel.endpoint())?;
+
+    let update = app
+        .updater_builder()
+        .endpoints(vec![endpoint])?
+        .version_comparator(move |current, remote| {
+            remote.version > current || (allow_downgrade && remote.version < current)
+        })
+        .build()?
+        .check()
+        .await?;
+    Ok(update)
+}
+
+fn update_available(app: &AppHandle, update: &Update) -> UpdateAvailable {
+    let current_version = app.package_info().version.clone();
+    UpdateAvailable {
+        version: update.version.clone(),
+        current_version: current_version.to_string(),
+        date: update.date.map(|d| d.to_string()),
+        body: update.body.clone(),
+        channel: channel(app),
+        downgrade: parse_version(&update.version).is_some_and(|version| version < current_version),
+    }
+}
+
+/// Whether a background check should keep `version` to itself. Clears the skip list first
+/// when `version` is newer than everything in it.
+fn is_deferred(app: &AppHandle, version: &str) -> bool {
+    let Some(store) = app.try_state::<UpdaterStore>() else {
+        return false;
+    };
+    let Ok(mut settings) = store.get() else {
+        return false;
+    };
+    if settings.supersedes_skipped(version) {
+        log::info!("Update {} is newer than the skipped versions, clearing them", version);
+        match store.update(|settings| settings.skipped_versions.clear()) {
+            Ok(updated) => settings = updated,
+            Err(e) => log::warn!("Failed to clear skipped versions: {}", e),
+        }
+    }
+    settings.is_deferred(version, Utc::now())
+}
+
+fn record_check(app: &AppHandle) {
+    if let Some(store) = app.try_state::<UpdaterStore>() {
+        let now = Utc::now().to_rfc3339();
+        if let Err(e) = store.update(|settings| settings.last_check_at = Some(now)) {
+            log::warn!("Failed to record update check: {}", e);
+        }
+    }
 }
 
 pub async fn check_for_updates(
     app: AppHandle,
     silent: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Checking for updates...");
+    log::info!("Checking for updates...");
 
-    let update = app.updater()?.check().await?;
+    let update = check_channel(&app, false).await?;
+    record_check(&app);
 
     if let Some(update) = update {
         let current_version = app.package_info().version.to_string();
         let new_version = update.version.clone();
 
-        println!(
-            "Update available: {} -> {}",
-            current_version, new_version
-        );
+        log::info!("Update available: {} -> {}", current_version, new_version);
 
         if !silent {
-            let _ = app.emit(
-                "update-available",
-                UpdateAvailable {
-                    version: new_version.clone(),
-                    current_version,
-                    date: update.date.map(|d| d.to_string()),
-                    body: update.body.clone(),
-                },
-            );
+            // Asking explicitly always shows the update, whatever was skipped or postponed
+            let _ = app.emit("update-available", update_available(&app, &update));
+        } else if is_deferred(&app, &new_version) {
+            log::info!("Update {} was skipped or postponed", new_version);
+        } else if !is_staged(&app, &new_version) {
+            // Background checks fetch the update quietly and only bother the user once it is ready.
+            // Waiting on the download lets a failed one count towards the scheduler's backoff.
+            if let Some(outcome) = start_download(&app, DownloadSource::Checked(update))? {
+                if let Ok(Err(e)) = outcome.await {
+                    return Err(format!("Failed to download update {}: {}", new_version, e).into());
+                }
+            }
         }
 
         Ok(())
     } else {
-        println!("App is up to date");
+        log::info!("App is up to date");
 
         if !silent {
             let _ = app.emit("update-not-available", ());
@@ -56,25 +329,119 @@ pub async fn check_for_updates(
     }
 }
 
-pub async fn download_and_install(
-    app: AppHandle,
-) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Starting update download...");
+/// A download kept on disk until the app quits or the user restarts; verified again before install
+struct StagedUpdate {
+    update: Update,
+    path: PathBuf,
+}
+
+/// The running background download and the update it left ready to install
+#[derive(Default)]
+pub struct UpdateStaging {
+    download: Mutex<Option<JoinHandle<()>>>,
+    ready: Mutex<Option<StagedUpdate>>,
+}
 
-    let update = app.updater()?.check().await?;
+impl UpdateStaging {
+    pub fn is_downloading(&self) -> bool {
+        self.download
+            .lock()
+            .is_ok_and(|task| task.as_ref().is_some_and(|task| !task.inner().is_finished()))
+    }
 
-    if let Some(update) = update {
-        let mut downloaded = 0;
+    /// Version of the update waiting to be installed
+    pub fn staged_version(&self) -> Option<String> {
+        self.ready
+            .lock()
+            .ok()
+            .and_then(|ready| ready.as_ref().map(|staged| staged.update.version.clone()))
+    }
+
+    /// Abort the download, returns whether one was running
+    fn cancel(&self) -> bool {
+        let task = self.download.lock().ok().and_then(|mut task| task.take());
+        match task {
+            Some(task) if !task.inner().is_finished() => {
+                task.abort();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn staging_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(app.path().app_cache_dir()?.join(STAGING_DIR))
+}
+
+fn update_failed(e: impl std::fmt::Display) -> AppError {
+    AppError::new(ErrorCode::UpdateFailed, e.to_string())
+}
+
+/// Remove staged files, except `keep`
+fn discard_staged(app: &AppHandle, keep: Option<&Path>) {
+    let Ok(entries) = staging_dir(app).and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if Some(path.as_path()) != keep {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+/// Check `bytes` against the signature from this run's update check, with the key from `tauri.conf.json`.
+/// The plugin only verifies what it downloads itself, and `Update::install` verifies nothing, so a file
+/// read back from the staging dir has to be checked again: anyone who can write there could swap it.
+fn verify_staged(app: &AppHandle, update: &Update, bytes: &[u8]) -> AppResult<()> {
+    let pubkey = app
+        .config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|config| config.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .ok_or_else(|| AppError::unavailable("Updater public key is not configured"))?;
+    let decode = |value: &str| -> AppResult<String> {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(update_failed)?;
+        String::from_utf8(decoded).map_err(update_failed)
+    };
 
-        update
-            .download_and_install(
+    let public_key = PublicKey::decode(&decode(pubkey)?).map_err(update_failed)?;
+    let signature = Signature::decode(&decode(&update.signature)?).map_err(update_failed)?;
+    public_key
+        .verify(bytes, &signature, true)
+        .map_err(|e| update_failed(format!("Update {} failed signature verification: {}", update.version, e)))
+}
+
+/// Download the update to disk. A file left by an earlier run is only reused once it verifies
+/// against the signature of the release just checked.
+async fn stage_update(app: &AppHandle, update: Update) -> AppResult<UpdateAvailable> {
+    let dir = staging_dir(app)?;
+    let path = dir.join(format!("{}.update", update.version));
+    let reusable = path.exists()
+        && match std::fs::read(&path)
+            .map_err(AppError::from)
+            .and_then(|bytes| verify_staged(app, &update, &bytes))
+        {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Discarding staged update {}: {}", update.version, e);
+                let _ = std::fs::remove_file(&path);
+                false
+            }
+        };
+    if reusable {
+        log::info!("Update {} is already downloaded", update.version);
+    } else {
+        log::info!("Downloading update {}...", update.version);
+        let mut downloaded = 0;
+        let bytes = update
+            .download(
                 |chunk_length, content_length| {
                     downloaded += chunk_length;
-                    println!(
-                        "Downloaded {} of {:?} bytes",
-                        downloaded, content_length
-                    );
-
                     let _ = app.emit(
                         "update-download-progress",
                         UpdateProgress {
@@ -83,52 +450,510 @@ pub async fn download_and_install(
                         },
                     );
                 },
-                || {
-                    println!("Download finished, preparing to install...");
-                    let _ = app.emit("update-ready-to-install", ());
-                },
+                || {},
             )
-            .await?;
+            .await
+            .map_err(update_failed)?;
 
-        println!("Update installed successfully");
-        let _ = app.emit("update-installed", ());
+        std::fs::create_dir_all(&dir)?;
+        let temp = path.with_extension("update.tmp");
+        std::fs::write(&temp, bytes)?;
+        std::fs::rename(&temp, &path)?;
+    }
+    discard_staged(app, Some(&path));
 
-        Ok(())
-    } else {
-        Err("No update available".into())
+    let available = update_available(app, &update);
+    if let Some(staging) = app.try_state::<UpdateStaging>() {
+        *staging.ready.lock()? = Some(StagedUpdate { update, path });
     }
+    Ok(available)
 }
 
-pub fn start_background_updater(app: AppHandle) {
-    std::thread::spawn(move || {
-        tauri::async_runtime::block_on(async move {
-            loop {
-                // Sleep for 24 hours (86400 seconds)
-                std::thread::sleep(std::time::Duration::from_secs(86400));
-                println!("Running scheduled update check...");
-
-                if let Err(e) = check_for_updates(app.clone(), true).await {
-                    eprintln!("Background update check failed: {}", e);
-                }
+fn is_staged(app: &AppHandle, version: &str) -> bool {
+    app.try_state::<UpdateStaging>().is_some_and(|staging| {
+        staging
+            .ready
+            .lock()
+            .is_ok_and(|ready| ready.as_ref().is_some_and(|staged| staged.update.version == version))
+    })
+}
+
+/// Where a background download gets its update from
+enum DownloadSource {
+    /// The update a check just found, so the channel isn't asked twice
+    Checked(Update),
+    /// Ask the channel first, as when the user starts the download
+    Channel { allow_downgrade: bool },
+}
+
+/// Start downloading in the background; emits `update-ready-to-install` once the update is on disk.
+/// The receiver gets the outcome, there is none when a download was already running.
+fn start_download(
+    app: &AppHandle,
+    source: DownloadSource,
+) -> AppResult<Option<oneshot::Receiver<Result<(), String>>>> {
+    let staging = app
+        .try_state::<UpdateStaging>()
+        .ok_or_else(|| AppError::unavailable("Updater is not available"))?;
+    if staging.is_downloading() {
+        return Ok(None);
+    }
+
+    let (done, outcome) = oneshot::channel();
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let update = match source {
+            DownloadSource::Checked(update) => Ok(Some(update)),
+            DownloadSource::Channel { allow_downgrade } => {
+                check_channel(&handle, allow_downgrade).await.map_err(update_failed)
             }
-        })
+        };
+        let staged = match update {
+            Ok(Some(update)) => stage_update(&handle, update).await.map(Some),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        };
+
+        let result = match staged {
+            Ok(Some(available)) => {
+                log::info!("Update {} is ready to install", available.version);
+                let _ = handle.emit("update-ready-to-install", available);
+                Ok(())
+            }
+            Ok(None) => {
+                let _ = handle.emit("update-not-available", ());
+                Ok(())
+            }
+            Err(e) => {
+                log::warn!("Failed to download update: {}", e);
+                let message = e.to_string();
+                let _ = handle.emit("update-download-failed", e);
+                Err(message)
+            }
+        };
+        let _ = done.send(result);
     });
+    *staging.download.lock()? = Some(task);
+    Ok(Some(outcome))
+}
+
+/// Install the staged update, returns whether there was one
+fn install_staged(app: &AppHandle) -> AppResult<bool> {
+    let Some(staging) = app.try_state::<UpdateStaging>() else {
+        return Ok(false);
+    };
+    let Some(staged) = staging.ready.lock()?.take() else {
+        return Ok(false);
+    };
 
-    println!("Background updater started (checks every 24 hours)");
+    log::info!("Installing update {}...", staged.update.version);
+    let bytes = std::fs::read(&staged.path)?;
+    if let Err(e) = verify_staged(app, &staged.update, &bytes) {
+        let _ = std::fs::remove_file(&staged.path);
+        return Err(e);
+    }
+    // On Windows this hands over to the installer and exits the process
+    staged.update.install(bytes).map_err(update_failed)?;
+    let _ = std::fs::remove_file(&staged.path);
+    let _ = app.emit("update-installed", ());
+    Ok(true)
+}
+
+/// Install a staged update as the app quits; called from the `Exit` run event
+pub fn install_staged_on_exit(app: &AppHandle) {
+    if let Some(staging) = app.try_state::<UpdateStaging>() {
+        staging.cancel();
+    }
+    if let Err(e) = install_staged(app) {
+        log::error!("Failed to install update on exit: {}", e);
+    }
+}
+
+/// Drop files left by earlier runs for versions that are no longer newer than this build
+pub fn prune_staged(app: &AppHandle) {
+    let current_version = &app.package_info().version;
+    let Ok(entries) = staging_dir(app).and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let newer = path.extension().is_some_and(|ext| ext == "update")
+            && path
+                .file_stem()
+                .and_then(|stem| parse_version(&stem.to_string_lossy()))
+                .is_some_and(|version| version > *current_version);
+        if !newer {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+/// Background update checks, woken early when the schedule changes
+#[derive(Default)]
+pub struct UpdateScheduler {
+    wake: Notify,
+    stopped: AtomicBool,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl UpdateScheduler {
+    /// Re-read the settings, e.g. after the interval or opt-out changed
+    fn reschedule(&self) {
+        self.wake.notify_one();
+    }
+
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.wake.notify_one();
+        if let Some(task) = self.task.lock().ok().and_then(|mut task| task.take()) {
+            task.abort();
+        }
+    }
+}
+
+/// Time to wait before the next scheduled check, or `None` while checks are off
+fn next_delay(app: &AppHandle, failures: u32) -> Option<Duration> {
+    app.try_state::<UpdaterStore>()?
+        .get()
+        .ok()?
+        .next_delay(failures, Utc::now())
+}
+
+async fn run_scheduler(app: AppHandle) {
+    let Some(scheduler) = app.try_state::<UpdateScheduler>() else {
+        return;
+    };
+    let mut failures = 0;
+
+    while !scheduler.stopped.load(Ordering::SeqCst) {
+        match next_delay(&app, failures) {
+            Some(delay) => {
+                log::debug!("Next update check in {} min", delay.as_secs() / 60);
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    // Settings changed or stopping: work out the delay again
+                    _ = scheduler.wake.notified() => continue,
+                }
+            }
+            None => {
+                scheduler.wake.notified().await;
+                continue;
+            }
+        }
+
+        log::info!("Running scheduled update check...");
+        match check_for_updates(app.clone(), true).await.map_err(|e| e.to_string()) {
+            Ok(()) => failures = 0,
+            Err(e) => {
+                failures += 1;
+                log::warn!("Background update check failed ({} in a row): {}", failures, e);
+            }
+        }
+    }
+}
+
+/// Check on startup when a check is overdue, then on the configured interval
+pub fn start_background_updater(app: AppHandle) {
+    app.manage(UpdateScheduler::default());
+    let task = tauri::async_runtime::spawn(run_scheduler(app.clone()));
+    if let Some(scheduler) = app.try_state::<UpdateScheduler>() {
+        if let Ok(mut slot) = scheduler.task.lock() {
+            *slot = Some(task);
+        }
+    }
+}
+
+/// Stop background checks, called when the app exits
+pub fn stop_background_updater(app: &AppHandle) {
+    if let Some(scheduler) = app.try_state::<UpdateScheduler>() {
+        scheduler.stop();
+    }
 }
 
 #[tauri::command]
 pub async fn manual_check_updates(
     app: AppHandle,
-) -> Result<(), String> {
+) -> AppResult<()> {
     check_for_updates(app, false)
         .await
-        .map_err(|e| format!("Failed to check for updates: {}", e))
+        .map_err(|e| update_failed(format!("Failed to check for updates: {}", e)))
 }
 
+/// Download the latest release of the selected channel in the background, to be installed on quit
+/// or with `restart_to_update`. Pass `allowDowngrade` to accept an older one.
 #[tauri::command]
-pub async fn install_update(app: AppHandle) -> Result<(), String> {
-    download_and_install(app)
-        .await
-        .map_err(|e| format!("Failed to install update: {}", e))
+pub fn download_update(app: AppHandle, allow_downgrade: Option<bool>) -> AppResult<()> {
+    let allow_downgrade = allow_downgrade.unwrap_or(false);
+    start_download(&app, DownloadSource::Channel { allow_downgrade })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_update_download(app: AppHandle, staging: State<UpdateStaging>) -> bool {
+    let canceled = staging.cancel();
+    if canceled {
+        log::info!("Update download canceled");
+        let _ = app.emit("update-download-canceled", ());
+    }
+    canceled
+}
+
+/// Install the downloaded update now and relaunch
+#[tauri::command]
+pub fn restart_to_update(app: AppHandle) -> AppResult<()> {
+    if !install_staged(&app).map_err(|e| e.context("Failed to install update"))? {
+        return Err(AppError::new(ErrorCode::NoUpdateAvailable, "No update has been downloaded"));
+    }
+    app.restart()
+}
+
+#[tauri::command]
+pub fn get_update_channel(store: State<UpdaterStore>) -> AppResult<UpdateChannel> {
+    store.get().map(|settings| settings.channel)
+}
+
+/// Switch channels. Moving to a more stable channel never downgrades by itself:
+/// the release it offers is returned as a downgrade for the UI to confirm with `download_update`.
+/// The switch stands even when that check fails, for example offline.
+#[tauri::command]
+pub async fn set_update_channel(app: AppHandle, channel: UpdateChannel) -> AppResult<ChannelSwitch> {
+    let store = app
+        .try_state::<UpdaterStore>()
+        .ok_or_else(|| AppError::unavailable("Updater is not available"))?;
+    store.update(|settings| settings.channel = channel)?;
+    log::info!("Update channel set to {:?}", channel);
+
+    let downgrade = match check_channel(&app, true).await {
+        Ok(update) => update
+            .map(|update| update_available(&app, &update))
+            .filter(|available| available.downgrade),
+        Err(e) => {
+            log::warn!("Failed to check the {:?} channel for updates: {}", channel, e);
+            None
+        }
+    };
+    Ok(ChannelSwitch { channel, downgrade })
+}
+
+/// Stop offering `version` in background checks; a staged download of it is thrown away
+#[tauri::command]
+pub fn skip_update_version(
+    app: AppHandle,
+    store: State<UpdaterStore>,
+    staging: State<UpdateStaging>,
+    version: String,
+) -> AppResult<UpdaterSettings> {
+    let settings = store.update(|settings| {
+        if !settings.skipped_versions.contains(&version) {
+            settings.skipped_versions.push(version.clone());
+        }
+    })?;
+
+    let mut ready = staging.ready.lock()?;
+    if ready.as_ref().is_some_and(|staged| staged.update.version == version) {
+        *ready = None;
+        discard_staged(&app, None);
+    }
+    log::info!("Skipping update {}", version);
+    Ok(settings)
+}
+
+/// Keep background checks quiet for `hours`
+#[tauri::command]
+pub fn remind_update_later(store: State<UpdaterStore>, hours: u32) -> AppResult<UpdaterSettings> {
+    if !(1..=MAX_CHECK_INTERVAL_HOURS).contains(&hours) {
+        return Err(AppError::invalid_input(format!(
+            "Reminder must be between 1 and {} hours away",
+            MAX_CHECK_INTERVAL_HOURS
+        )));
+    }
+    let remind_after = Utc::now() + chrono::Duration::hours(i64::from(hours));
+    store.update(|settings| settings.remind_after = Some(remind_after.to_rfc3339()))
+}
+
+#[tauri::command]
+pub fn get_updater_settings(store: State<UpdaterStore>) -> AppResult<UpdaterSettings> {
+    store.get()
+}
+
+/// Turn background checks on or off and change how often they run
+#[tauri::command]
+pub fn set_update_schedule(
+    store: State<UpdaterStore>,
+    scheduler: State<UpdateScheduler>,
+    auto_check: Option<bool>,
+    check_interval_hours: Option<u32>,
+) -> AppResult<UpdaterSettings> {
+    if let Some(hours) = check_interval_hours {
+        if !(1..=MAX_CHECK_INTERVAL_HOURS).contains(&hours) {
+            return Err(AppError::invalid_input(format!(
+                "Check interval must be between 1 and {} hours",
+                MAX_CHECK_INTERVAL_HOURS
+            )));
+        }
+    }
+
+    let settings = store.update(|settings| {
+        if let Some(auto_check) = auto_check {
+            settings.auto_check = auto_check;
+        }
+        if let Some(hours) = check_interval_hours {
+            settings.check_interval_hours = hours;
+        }
+    })?;
+    scheduler.reschedule();
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc)
+    }
+
+    fn hours(hours: u64) -> Duration {
+        Duration::from_secs(hours * 3600)
+    }
+
+    fn checked_at(last_check_at: Option<&str>) -> UpdaterSettings {
+        UpdaterSettings {
+            last_check_at: last_check_at.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_first_check_is_due_at_once() {
+        let now = at("2025-01-02T00:00:00Z");
+        assert_eq!(checked_at(None).until_due(now), Duration::ZERO);
+        // An unreadable date counts as never checked
+        assert_eq!(checked_at(Some("yesterday")).until_due(now), Duration::ZERO);
+    }
+
+    #[test]
+    fn until_due_counts_down_the_interval() {
+        let settings = checked_at(Some("2025-01-01T00:00:00Z"));
+        assert_eq!(settings.until_due(at("2025-01-01T06:00:00Z")), hours(18));
+        assert_eq!(settings.until_due(at("2025-01-02T00:00:00Z")), Duration::ZERO);
+        // A clock that went backwards waits the whole interval rather than overflowing
+        assert_eq!(settings.until_due(at("2024-12-31T00:00:00Z")), hours(24));
+    }
+
+    #[test]
+    fn an_overdue_check_is_due_at_once() {
+        let settings = checked_at(Some("2025-01-01T00:00:00Z"));
+        assert_eq!(settings.until_due(at("2025-01-09T00:00:00Z")), Duration::ZERO);
+    }
+
+    #[test]
+    fn the_interval_is_clamped() {
+        let interval = |check_interval_hours| {
+            UpdaterSettings {
+                check_interval_hours,
+                ..Default::default()
+            }
+            .interval()
+        };
+        assert_eq!(interval(0), hours(1));
+        assert_eq!(interval(6), hours(6));
+        assert_eq!(interval(10_000), hours(u64::from(MAX_CHECK_INTERVAL_HOURS)));
+    }
+
+    #[test]
+    fn disabled_checks_are_never_scheduled() {
+        let settings = UpdaterSettings {
+            auto_check: false,
+            ..Default::default()
+        };
+        let now = at("2025-01-02T00:00:00Z");
+        assert_eq!(settings.next_delay(0, now), None);
+        assert_eq!(settings.next_delay(3, now), None);
+    }
+
+    #[test]
+    fn next_delay_follows_the_schedule_until_a_check_fails() {
+        let settings = checked_at(Some("2025-01-01T00:00:00Z"));
+        assert_eq!(settings.next_delay(0, at("2025-01-01T20:00:00Z")), Some(hours(4)));
+        assert_eq!(settings.next_delay(1, at("2025-01-01T20:00:00Z")), Some(RETRY_BASE));
+    }
+
+    #[test]
+    fn backoff_doubles_with_each_failure() {
+        assert_eq!(retry_backoff(1), RETRY_BASE);
+        assert_eq!(retry_backoff(2), RETRY_BASE * 2);
+        assert_eq!(retry_backoff(4), RETRY_BASE * 8);
+        // Never overflows however long checks keep failing
+        assert!(retry_backoff(u32::MAX) >= retry_backoff(40));
+    }
+
+    #[test]
+    fn backoff_is_capped_at_the_interval() {
+        let settings = UpdaterSettings {
+            check_interval_hours: 1,
+            ..Default::default()
+        };
+        let now = at("2025-01-02T00:00:00Z");
+        assert_eq!(settings.next_delay(2, now), Some(RETRY_BASE * 2));
+        assert_eq!(settings.next_delay(5, now), Some(hours(1)));
+        assert_eq!(settings.next_delay(100, now), Some(hours(1)));
+    }
+
+    fn deferring(skipped_versions: &[&str], remind_after: Option<&str>) -> UpdaterSettings {
+        UpdaterSettings {
+            skipped_versions: skipped_versions.iter().map(|v| v.to_string()).collect(),
+            remind_after: remind_after.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn remind_later_defers_every_version_until_it_expires() {
+        let settings = deferring(&[], Some("2025-01-02T00:00:00Z"));
+        assert!(settings.is_deferred("1.2.0", at("2025-01-01T12:00:00Z")));
+        assert!(settings.is_deferred("9.0.0", at("2025-01-01T12:00:00Z")));
+        assert!(!settings.is_deferred("1.2.0", at("2025-01-02T00:00:00Z")));
+        assert!(!settings.is_deferred("1.2.0", at("2025-01-03T00:00:00Z")));
+    }
+
+    #[test]
+    fn an_unreadable_remind_after_defers_nothing() {
+        let settings = deferring(&[], Some("tomorrow"));
+        assert!(!settings.is_deferred("1.2.0", at("2025-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn a_skipped_version_stays_deferred() {
+        let settings = deferring(&["1.2.0"], None);
+        let now = at("2025-01-01T00:00:00Z");
+        assert!(settings.is_deferred("1.2.0", now));
+        assert!(!settings.is_deferred("1.2.1", now));
+        assert!(!settings.supersedes_skipped("1.2.0"));
+    }
+
+    #[test]
+    fn a_newer_version_supersedes_the_skip_list() {
+        let settings = deferring(&["1.2.0", "v1.1.0"], None);
+        assert!(settings.supersedes_skipped("1.3.0"));
+        assert!(settings.supersedes_skipped("v1.2.1"));
+        assert!(!settings.supersedes_skipped("1.1.5"));
+        assert!(!settings.supersedes_skipped("not-a-version"));
+
+        // What `is_deferred` does on finding one: the list goes, and the new version is offered
+        let mut settings = settings;
+        settings.skipped_versions.clear();
+        assert!(!settings.is_deferred("1.3.0", at("2025-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn an_empty_skip_list_is_never_superseded() {
+        assert!(!deferring(&[], None).supersedes_skipped("1.3.0"));
+    }
+
+    #[test]
+    fn prereleases_supersede_only_older_releases() {
+        let settings = deferring(&["1.2.0"], None);
+        assert!(!settings.supersedes_skipped("1.2.0-beta.1"));
+        assert!(settings.supersedes_skipped("1.3.0-beta.1"));
+    }
 }
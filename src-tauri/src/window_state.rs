@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalRect, PhysicalSize, WebviewWindow, Window, WindowEvent,
+};
+
+use crate::error::{AppError, AppResult};
+
+const STATE_FILE: &str = "window-state.json";
+
+/// How much of a window has to be on a monitor, both across and down from its top edge,
+/// for it to count as reachable; enough to grab the title bar and drag it back
+const MIN_VISIBLE: u32 = 100;
+
+/// Bounds and mode of one window. Position and size are physical pixels; the size is the inner size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Name of the monitor the window was on, when the platform reports one
+    pub monitor: Option<String>,
+    /// Scale factor of that monitor, to keep the size when the window lands on another one
+    pub scale_factor: f64,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
+impl WindowState {
+    /// Pixels of the window across, and of its top edge strip down, that fall inside `area`
+    fn overlap(&self, area: &PhysicalRect<i32, u32>) -> (i64, i64) {
+        let overlap = |start: i32, length: u32, area_start: i32, area_length: u32| {
+            let end = i64::from(start) + i64::from(length);
+            let area_end = i64::from(area_start) + i64::from(area_length);
+            (end.min(area_end) - i64::from(start).max(i64::from(area_start))).max(0)
+        };
+        (
+            overlap(self.x, self.width, area.position.x, area.size.width),
+            overlap(self.y, self.height.min(MIN_VISIBLE), area.position.y, area.size.height),
+        )
+    }
+
+    /// Area of the top edge strip inside `area`, to pick the monitor holding most of the window
+    fn visible_in(&self, area: &PhysicalRect<i32, u32>) -> i64 {
+        let (across, down) = self.overlap(area);
+        across * down
+    }
+
+    /// Whether enough of the title bar is inside `area`, both across and down, to drag the window by it
+    fn reachable_in(&self, area: &PhysicalRect<i32, u32>) -> bool {
+        let (across, down) = self.overlap(area);
+        across >= i64::from(self.width.min(MIN_VISIBLE)) && down >= i64::from(self.height.min(MIN_VISIBLE))
+    }
+
+    /// Fit the window inside a monitor's work area, rescaled when it was saved at a different scale factor
+    fn clamped_to(
+        &self,
+        area: &PhysicalRect<i32, u32>,
+        scale_factor: f64,
+    ) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        let scale = if self.scale_factor > 0.0 {
+            scale_factor / self.scale_factor
+        } else {
+            1.0
+        };
+        let width = ((f64::from(self.width) * scale).round() as u32).min(area.size.width);
+        let height = ((f64::from(self.height) * scale).round() as u32).min(area.size.height);
+
+        let max_x = area.position.x + (area.size.width - width) as i32;
+        let max_y = area.position.y + (area.size.height - height) as i32;
+        let position = PhysicalPosition::new(
+            self.x.clamp(area.position.x, max_x),
+            self.y.clamp(area.position.y, max_y),
+        );
+        (position, PhysicalSize::new(width, height))
+    }
+}
+
+/// Saved state of every window, by label. Kept current from window events and written on close and exit.
+pub struct WindowStateStore {
+    path: Option<PathBuf>,
+    windows: Mutex<HashMap<String, WindowState>>,
+    /// Windows restored while hidden, still to be maximized or made fullscreen once shown:
+    /// doing it earlier can show them on some platforms, defeating start minimized
+    pending_mode: Mutex<HashSet<String>>,
+}
+
+impl WindowStateStore {
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app.path().app_config_dir().ok().map(|dir| dir.join(STATE_FILE));
+        let windows = match path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {}: {}", STATE_FILE, e);
+                HashMap::new()
+            }),
+            _ => HashMap::new(),
+        };
+
+        Self {
+            path,
+            windows: Mutex::new(windows),
+            pending_mode: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn get(&self, label: &str) -> AppResult<Option<WindowState>> {
+        Ok(self.windows.lock()?.get(label).cloned())
+    }
+
+    fn set(&self, label: &str, state: WindowState) -> AppResult<()> {
+        self.windows.lock()?.insert(label.to_string(), state);
+        Ok(())
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        let Some(path) = &self.path else {
+            return Err(AppError::unavailable("App config directory is not available"));
+        };
+        let content = serde_json::to_string_pretty(&*self.windows.lock()?)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+/// Where the window is now, `None` while it is minimized or hidden and its bounds mean nothing.
+/// While maximized or fullscreen the last normal bounds are kept, so leaving that mode goes back to them.
+fn snapshot(window: &Window, previous: Option<WindowState>) -> AppResult<Option<WindowState>> {
+    if window.is_minimized()? || !window.is_visible()? {
+        return Ok(None);
+    }
+    let maximized = window.is_maximized()?;
+    let fullscreen = window.is_fullscreen()?;
+    if let Some(previous) = previous.filter(|_| maximized || fullscreen) {
+        return Ok(Some(WindowState {
+            maximized,
+            fullscreen,
+            ..previous
+        }));
+    }
+
+    let position = window.outer_position()?;
+    let size = window.inner_size()?;
+    let monitor = window.current_monitor()?;
+    Ok(Some(WindowState {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        monitor: monitor.as_ref().and_then(|monitor| monitor.name().cloned()),
+        scale_factor: monitor
+            .as_ref()
+            .map(|monitor| monitor.scale_factor())
+            .unwrap_or(window.scale_factor()?),
+        maximized,
+        fullscreen,
+    }))
+}
+
+fn record(window: &Window) -> AppResult<()> {
+    let Some(store) = window.try_state::<WindowStateStore>() else {
+        return Ok(());
+    };
+    let label = window.label();
+    if let Some(state) = snapshot(window, store.get(label)?)? {
+        store.set(label, state)?;
+    }
+    Ok(())
+}
+
+/// Write what was recorded; called when a window closes and when the app exits
+pub fn save(app: &AppHandle) {
+    if let Some(store) = app.try_state::<WindowStateStore>() {
+        if let Err(e) = store.save() {
+            log::warn!("Failed to save window state: {}", e);
+        }
+    }
+}
+
+/// Keep the store current as windows move, resize or change mode; registered on the builder
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. } => {
+            if let Err(e) = record(window) {
+                log::warn!("Failed to record state of window {}: {}", window.label(), e);
+            }
+        }
+        WindowEvent::Focused(true) => {
+            if let Err(e) = apply_pending_mode(window) {
+                log::warn!("Failed to restore mode of window {}: {}", window.label(), e);
+            }
+        }
+        WindowEvent::CloseRequested { .. } | WindowEvent::Destroyed => save(window.app_handle()),
+        _ => {}
+    }
+}
+
+/// Put the window back where it was. A window that would be out of reach, because its monitor was
+/// unplugged or rearranged, goes onto the monitor it was on if still connected, else the primary one.
+fn restore_window(window: &WebviewWindow, state: &WindowState) -> AppResult<()> {
+    let monitors = window.available_monitors()?;
+    if monitors.is_empty() {
+        return Ok(());
+    }
+
+    let reachable = monitors.iter().any(|monitor| state.reachable_in(monitor.work_area()));
+    let (position, size) = if reachable {
+        let monitor = monitors
+            .iter()
+            .max_by_key(|monitor| state.visible_in(monitor.work_area()))
+            .unwrap_or(&monitors[0]);
+        // Left where it was, only trimmed to fit a monitor that may have shrunk
+        let area = monitor.work_area();
+        (
+            PhysicalPosition::new(state.x, state.y),
+            PhysicalSize::new(state.width.min(area.size.width), state.height.min(area.size.height)),
+        )
+    } else {
+        let primary = window.primary_monitor()?;
+        let monitor = monitors
+            .iter()
+            .find(|monitor| state.monitor.is_some() && monitor.name() == state.monitor.as_ref())
+            .or(primary.as_ref())
+            .unwrap_or(&monitors[0]);
+        log::info!(
+            "Window {} was out of reach, moving it onto {}",
+            window.label(),
+            monitor.name().map(String::as_str).unwrap_or("the primary monitor")
+        );
+        state.clamped_to(monitor.work_area(), monitor.scale_factor())
+    };
+
+    window.set_size(size)?;
+    window.set_position(position)?;
+    if !(state.maximized || state.fullscreen) {
+        return Ok(());
+    }
+    if window.is_visible()? {
+        apply_mode(window, state)
+    } else {
+        let store = window.state::<WindowStateStore>();
+        store.pending_mode.lock()?.insert(window.label().to_string());
+        Ok(())
+    }
+}
+
+fn apply_mode(window: &WebviewWindow, state: &WindowState) -> AppResult<()> {
+    if state.maximized {
+        window.maximize()?;
+    }
+    if state.fullscreen {
+        window.set_fullscreen(true)?;
+    }
+    Ok(())
+}
+
+fn apply_pending_mode(window: &Window) -> AppResult<()> {
+    let Some(store) = window.try_state::<WindowStateStore>() else {
+        return Ok(());
+    };
+    if !store.pending_mode.lock()?.remove(window.label()) {
+        return Ok(());
+    }
+    let webview_window = window
+        .get_webview_window(window.label())
+        .ok_or_else(|| AppError::window_not_found(window.label()))?;
+    match store.get(window.label())? {
+        Some(state) => apply_mode(&webview_window, &state),
+        None => Ok(()),
+    }
+}
+
+/// Restore every window with a saved state; windows start hidden, so call before showing them
+pub fn restore(app: &AppHandle) {
+    let Some(store) = app.try_state::<WindowStateStore>() else {
+        return;
+    };
+    for (label, window) in app.webview_windows() {
+        let state = match store.get(&label) {
+            Ok(Some(state)) => state,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Failed to read state of window {}: {}", label, e);
+                continue;
+            }
+        };
+        if let Err(e) = restore_window(&window, &state) {
+            log::warn!("Failed to restore window {}: {}", label, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(x: i32, y: i32, width: u32, height: u32) -> WindowState {
+        WindowState {
+            x,
+            y,
+            width,
+            height,
+            monitor: None,
+            scale_factor: 1.0,
+            maximized: false,
+            fullscreen: false,
+        }
+    }
+
+    fn area(x: i32, y: i32, width: u32, height: u32) -> PhysicalRect<i32, u32> {
+        PhysicalRect {
+            position: PhysicalPosition::new(x, y),
+            size: PhysicalSize::new(width, height),
+        }
+    }
+
+    fn clamped(state: &WindowState, area: &PhysicalRect<i32, u32>, scale: f64) -> (i32, i32, u32, u32) {
+        let (position, size) = state.clamped_to(area, scale);
+        (position.x, position.y, size.width, size.height)
+    }
+
+    #[test]
+    fn visible_in_counts_only_the_top_edge_strip() {
+        let screen = area(0, 0, 1920, 1080);
+        assert_eq!(state(100, 100, 800, 600).visible_in(&screen), 800 * 100);
+        assert_eq!(state(1820, 100, 800, 600).visible_in(&screen), 100 * 100);
+        assert_eq!(state(100, 1050, 800, 600).visible_in(&screen), 800 * 30);
+        assert_eq!(state(-900, 100, 800, 600).visible_in(&screen), 0);
+    }
+
+    #[test]
+    fn a_window_fully_on_screen_is_reachable() {
+        let screen = area(0, 0, 1920, 1080);
+        assert!(state(100, 100, 800, 600).reachable_in(&screen));
+        // Small windows only need to be wholly on screen
+        assert!(state(10, 10, 50, 40).reachable_in(&screen));
+    }
+
+    #[test]
+    fn a_thin_sliver_on_screen_is_not_reachable() {
+        let screen = area(0, 0, 1920, 1080);
+        // 5 px across, even with the whole title bar down
+        assert!(!state(1915, 100, 800, 600).reachable_in(&screen));
+        // The full width across, but only 20 px of the title bar down
+        assert!(!state(100, 1060, 800, 600).reachable_in(&screen));
+        assert!(!state(100, -580, 800, 600).reachable_in(&screen));
+        // Exactly enough both ways
+        assert!(state(1820, 980, 800, 600).reachable_in(&screen));
+    }
+
+    #[test]
+    fn reachable_in_respects_the_monitor_offset() {
+        let right = area(1920, 0, 2560, 1440);
+        assert!(!state(100, 100, 800, 600).reachable_in(&right));
+        assert!(state(2000, 100, 800, 600).reachable_in(&right));
+    }
+
+    #[test]
+    fn clamped_to_moves_the_window_inside_the_work_area() {
+        let screen = area(0, 40, 1920, 1040);
+        assert_eq!(clamped(&state(3000, -200, 800, 600), &screen, 1.0), (1120, 40, 800, 600));
+        assert_eq!(clamped(&state(-500, 900, 800, 600), &screen, 1.0), (0, 480, 800, 600));
+    }
+
+    #[test]
+    fn clamped_to_shrinks_a_window_larger_than_the_work_area() {
+        let screen = area(0, 0, 1280, 720);
+        assert_eq!(clamped(&state(100, 100, 1920, 1080), &screen, 1.0), (0, 0, 1280, 720));
+    }
+
+    #[test]
+    fn clamped_to_rescales_between_scale_factors() {
+        let screen = area(0, 0, 3840, 2160);
+        assert_eq!(clamped(&state(0, 0, 800, 600), &screen, 2.0), (0, 0, 1600, 1200));
+
+        let mut unscaled = state(0, 0, 800, 600);
+        unscaled.scale_factor = 0.0;
+        assert_eq!(clamped(&unscaled, &screen, 2.0), (0, 0, 800, 600));
+    }
+}
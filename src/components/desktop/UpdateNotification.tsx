@@ -14,7 +14,11 @@ interface UpdateNotificationProps {
     downloadProgress: number;
     readyToInstall: boolean;
     downloadAndInstall: () => void;
+    cancelDownload: () => void;
+    restartToUpdate: () => void;
     dismissUpdate: () => void;
+    remindLater: () => void;
+    skipVersion: () => void;
   };
 }
 
@@ -26,7 +30,11 @@ export function UpdateNotification({ updater }: UpdateNotificationProps) {
     downloadProgress,
     readyToInstall,
     downloadAndInstall,
+    cancelDownload,
+    restartToUpdate,
     dismissUpdate,
+    remindLater,
+    skipVersion,
   } = updater;
 
   if (!updateAvailable || !updateInfo) return null;
@@ -47,7 +55,9 @@ export function UpdateNotification({ updater }: UpdateNotificationProps) {
                 Update Available
               </h3>
               <p className="text-sm text-white/60 mt-1">
-                Version {updateInfo.version} is ready to download
+                {readyToInstall
+                  ? `Version ${updateInfo.version} will be installed when you quit`
+                  : `Version ${updateInfo.version} is ready to download`}
               </p>
               {updateInfo.body && (
                 <p className="text-xs text-white/50 mt-2 line-clamp-2">
@@ -93,13 +103,23 @@ export function UpdateNotification({ updater }: UpdateNotificationProps) {
 
           <div className="flex items-center gap-2 mt-4">
             {readyToInstall ? (
-              <Button
-                onClick={downloadAndInstall}
-                className="button-primary flex-1"
-                size="sm"
-              >
-                Restart to Install
-              </Button>
+              <>
+                <Button
+                  onClick={restartToUpdate}
+                  className="button-primary flex-1"
+                  size="sm"
+                >
+                  Restart Now
+                </Button>
+                <Button
+                  onClick={dismissUpdate}
+                  variant="flat"
+                  size="sm"
+                  className="text-white/60"
+                >
+                  On Quit
+                </Button>
+              </>
             ) : (
               <>
                 <Button
@@ -109,16 +129,26 @@ export function UpdateNotification({ updater }: UpdateNotificationProps) {
                   isLoading={downloading}
                   isDisabled={downloading}
                 >
-                  {downloading ? 'Downloading...' : 'Download & Install'}
+                  {downloading ? 'Downloading...' : 'Download'}
                 </Button>
                 <Button
-                  onClick={dismissUpdate}
+                  onClick={downloading ? cancelDownload : remindLater}
                   variant="flat"
                   size="sm"
                   className="text-white/60"
                 >
-                  Later
+                  {downloading ? 'Cancel' : 'Later'}
                 </Button>
+                {!downloading && (
+                  <Button
+                    onClick={skipVersion}
+                    variant="light"
+                    size="sm"
+                    className="text-white/40"
+                  >
+                    Skip
+                  </Button>
+                )}
               </>
             )}
           </div>
@@ -14,7 +14,11 @@ export interface UpdaterState {
   readyToInstall: boolean;
   checkForUpdates: (showToast?: boolean) => Promise<void>;
   downloadAndInstall: () => Promise<void>;
+  cancelDownload: () => Promise<void>;
+  restartToUpdate: () => Promise<void>;
   dismissUpdate: () => void;
+  remindLater: () => Promise<void>;
+  skipVersion: () => Promise<void>;
 }
 
 export interface DesktopActionsContextType {
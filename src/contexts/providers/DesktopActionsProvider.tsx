@@ -3,6 +3,10 @@ import { useNavigate } from 'react-router';
 import { DesktopActionsContext } from '../DesktopActionsContext';
 import { useSyncStore } from '@/stores/useSyncStore';
 import { useUpdater } from '@/hooks/desktop/useUpdater';
+import { useDeepLinks } from '@/hooks/desktop/useDeepLinks';
+import { useNativeLibrary } from '@/hooks/desktop/useNativeLibrary';
+import { useDroppedImports } from '@/hooks/desktop/useDroppedImports';
+import { useCrashReports } from '@/hooks/desktop/useCrashReports';
 import { UpdateNotification } from '@/components/desktop/UpdateNotification';
 import { isDesktop } from '@/lib/platform';
 import { useUIStore } from '@/stores/useUIStore';
@@ -25,6 +29,10 @@ export function DesktopActionsProvider({ children }: { children: React.ReactNode
   const toggleFilters = useUIStore((state) => state.toggleFilters);
   const openImportExport = useUIStore((state) => state.openImportExport);
   const updater = useUpdater();
+  useDeepLinks();
+  useNativeLibrary();
+  useDroppedImports();
+  useCrashReports();
 
   const checkForUpdates = useCallback(() => {
     updater.checkForUpdates();
@@ -72,6 +80,8 @@ export function DesktopActionsProvider({ children }: { children: React.ReactNode
 
         // Global keyboard shortcuts (from shortcuts.rs)
         listen('shortcut:quick-add', openQuickAdd),
+        listen<string>('shortcut:navigate', (event: Event<string>) => navigate(event.payload)),
+        listen('shortcut:sync-now', triggerSync),
       ]);
 
       return () => {
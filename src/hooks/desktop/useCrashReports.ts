@@ -0,0 +1,51 @@
+import { useEffect, useRef } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { openUrl } from '@tauri-apps/plugin-opener';
+import { getWindowLabel, isDesktop } from '@/lib/platform';
+import { useConfirmationModal } from '@/contexts/ConfirmationModalContext';
+
+interface CrashReport {
+  id: string;
+  appVersion: string;
+  occurredAt: string;
+  message: string;
+  location: string | null;
+}
+
+/**
+ * Offer to report crashes recorded by the panic hook (crash.rs) since the app last ran
+ */
+export function useCrashReports() {
+  const { confirm } = useConfirmationModal();
+  // Opening the modal changes `confirm`; asking again then would stack a second prompt
+  const confirmRef = useRef(confirm);
+  useEffect(() => {
+    confirmRef.current = confirm;
+  }, [confirm]);
+
+  useEffect(() => {
+    if (!isDesktop() || getWindowLabel() !== 'main') return;
+
+    let cancelled = false;
+    const showReports = async () => {
+      const reports = await invoke<CrashReport[]>('get_crash_reports');
+      if (cancelled || reports.length === 0) return;
+
+      const [latest] = reports;
+      const report = await confirmRef.current({
+        title: 'Watchfolio Closed Unexpectedly',
+        message: `${reports.length > 1 ? `${reports.length} crashes were` : 'A crash was'} recorded last time: "${latest.message}". Reporting it helps get it fixed.`,
+        confirmText: 'Report Issue',
+        cancelText: 'Dismiss',
+      });
+      if (report) await openUrl(await invoke<string>('issue_report_url'));
+
+      await Promise.all(reports.map((crash) => invoke('dismiss_crash_report', { id: crash.id })));
+    };
+
+    showReports().catch((error) => log('ERR', 'Failed to show crash reports:', error));
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+}
@@ -0,0 +1,20 @@
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { isDesktop } from '@/lib/platform';
+
+/**
+ * Make the title shown on this page the target of the tray's Quick Status and the mark-current shortcuts
+ */
+export function useCurrentMedia(type: MediaType, tmdbId?: number, title?: string) {
+  useEffect(() => {
+    if (!isDesktop() || !tmdbId || !title) return;
+
+    const setCurrent = (media: { tmdbId: number; media_type: MediaType; title: string } | null) =>
+      invoke('set_current_media', { media }).catch((error) => log('ERR', 'Failed to set current media:', error));
+
+    setCurrent({ tmdbId, media_type: type, title });
+    return () => {
+      setCurrent(null);
+    };
+  }, [type, tmdbId, title]);
+}
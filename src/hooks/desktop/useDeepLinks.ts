@@ -0,0 +1,81 @@
+import { useEffect } from 'react';
+import { useNavigate } from 'react-router';
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { addToast } from '@heroui/react';
+import { getDetails } from '@/lib/api/TMDB';
+import { getLibraryItemByTmdbId } from '@/lib/rxdb';
+import { errorMessage, getWindowLabel, isDesktop } from '@/lib/platform';
+import { useAddOrUpdateLibraryItem } from '@/hooks/library/useLibraryMutations';
+import { generateMediaId } from '@/utils/library';
+
+interface DeepLinkAdd {
+  tmdbId: number;
+  media_type: MediaType;
+  status: WatchStatus;
+  inBackground: boolean;
+}
+
+interface RejectedLink {
+  url: string;
+  message: string;
+}
+
+/**
+ * Handle watchfolio:// links routed to the main window by deep_link.rs
+ */
+export function useDeepLinks() {
+  const navigate = useNavigate();
+  const { mutateAsync: addOrUpdateItem } = useAddOrUpdateLibraryItem();
+
+  useEffect(() => {
+    if (!isDesktop() || getWindowLabel() !== 'main') return;
+
+    let unlisteners: UnlistenFn[] = [];
+    let disposed = false;
+
+    const addFromLink = async ({ tmdbId, media_type, status, inBackground }: DeepLinkAdd) => {
+      // A hidden window can't show toasts
+      const confirm = (title: string, description: string, color: 'success' | 'danger') =>
+        inBackground
+          ? invoke('show_tray_notification', { title, body: description }).catch((error) =>
+              log('ERR', 'Failed to show notification:', error)
+            )
+          : addToast({ title, description, color });
+
+      try {
+        const details = await getDetails(media_type, tmdbId, false);
+        if (!details) throw new Error(`No ${media_type} with TMDB id ${tmdbId}`);
+
+        const media = { ...details, media_type };
+        const existing = await getLibraryItemByTmdbId(tmdbId, media_type);
+        await addOrUpdateItem({ item: { id: existing?.id ?? generateMediaId(media), status }, media });
+
+        confirm('Added to library', (media as Movie).title || (media as TvShow).name, 'success');
+      } catch (error) {
+        confirm("Couldn't add to library", errorMessage(error), 'danger');
+      }
+    };
+
+    Promise.all([
+      listen<string>('deep-link:navigate', (event) => navigate(event.payload)),
+      listen<DeepLinkAdd>('deep-link:add', (event) => addFromLink(event.payload)),
+      listen<RejectedLink>('deep-link:error', (event) =>
+        addToast({ title: "Couldn't open link", description: event.payload.message, color: 'danger' })
+      ),
+    ]).then((fns) => {
+      if (disposed) {
+        fns.forEach((fn) => fn());
+        return;
+      }
+      unlisteners = fns;
+      // Links from launch are held back until now
+      invoke('deep_links_ready').catch((error) => console.error('Failed to flush deep links:', error));
+    });
+
+    return () => {
+      disposed = true;
+      unlisteners.forEach((fn) => fn());
+    };
+  }, [navigate, addOrUpdateItem]);
+}
@@ -0,0 +1,102 @@
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { addToast } from '@heroui/react';
+import { getLibraryItemsByIds } from '@/lib/rxdb';
+import { errorMessage, getWindowLabel, isDesktop } from '@/lib/platform';
+import { useConfirmationModal } from '@/contexts/ConfirmationModalContext';
+import { useImportLibrary } from '@/hooks/library/useLibraryMutations';
+
+interface DroppedFile {
+  path: string;
+  source: string | null;
+  error: string | null;
+}
+
+interface ImportPreview {
+  previewId: number;
+  files: DroppedFile[];
+  totalItems: number;
+  newItems: number;
+  updatedItems: number;
+  conflictingItems: number;
+  invalidItems: number;
+  unresolvedItems: number;
+}
+
+interface DroppedImportResult {
+  items: LibraryMedia[];
+}
+
+const fileName = (path: string) => path.split(/[\\/]/).pop() || path;
+
+const describe = (preview: ImportPreview) =>
+  [
+    `${preview.newItems} new and ${preview.updatedItems} updated titles will be imported.`,
+    preview.conflictingItems > 0 &&
+      `${preview.conflictingItems} titles changed in your library since the export and will be kept as they are.`,
+    preview.invalidItems > 0 && `${preview.invalidItems} invalid rows will be skipped.`,
+    preview.unresolvedItems > 0 &&
+      `${preview.unresolvedItems} titles without a TMDB match will be skipped; import the file from Settings to match them.`,
+  ]
+    .filter(Boolean)
+    .join(' ');
+
+/**
+ * Confirm and apply import files dropped onto the main window, parsed natively by file_drop.rs
+ */
+export function useDroppedImports() {
+  const { confirm } = useConfirmationModal();
+  const { mutateAsync: importLibrary } = useImportLibrary();
+
+  useEffect(() => {
+    if (!isDesktop() || getWindowLabel() !== 'main') return;
+
+    const handlePreview = async (preview: ImportPreview) => {
+      const { previewId } = preview;
+      preview.files
+        .filter((file) => file.error)
+        .forEach((file) =>
+          addToast({ title: `Couldn't import ${fileName(file.path)}`, description: file.error, color: 'danger' })
+        );
+
+      try {
+        const importable = preview.newItems + preview.updatedItems;
+        const confirmed =
+          importable > 0 &&
+          (await confirm({
+            title: 'Import Dropped Files',
+            message: describe(preview),
+            confirmText: 'Import',
+          }));
+        if (!confirmed) {
+          await invoke('discard_dropped_import', { previewId });
+          return;
+        }
+
+        const results = await invoke<DroppedImportResult[]>('take_dropped_import', { previewId });
+        const items = results.flatMap((result) => result.items);
+        // Keep library copies changed after the export, as the smart merge of the import dialog does
+        const existing = new Map((await getLibraryItemsByIds(items.map((item) => item.id))).map((item) => [item.id, item]));
+        const toImport = items.filter((item) => {
+          const current = existing.get(item.id);
+          return !current || new Date(current.lastUpdatedAt) <= new Date(item.lastUpdatedAt);
+        });
+
+        await importLibrary(toImport);
+        addToast({
+          title: 'Import Complete!',
+          description: `Your library has been successfully updated with ${toImport.length} items.`,
+          color: 'success',
+        });
+      } catch (error) {
+        addToast({ title: 'Import Failed', description: errorMessage(error), color: 'danger' });
+      }
+    };
+
+    const unlisten = listen<ImportPreview>('import:preview', (event) => handlePreview(event.payload));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [confirm, importLibrary]);
+}
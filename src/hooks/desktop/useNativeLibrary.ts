@@ -0,0 +1,100 @@
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { Subscription } from 'rxjs';
+import { subscribeToDB, type WatchfolioDatabase } from '@/lib/rxdb';
+import { getWindowLabel, isDesktop } from '@/lib/platform';
+
+interface LibraryChange {
+  kind: 'upserted' | 'deleted' | 'cleared';
+  ids: string[];
+}
+
+const isNewer = (item: LibraryMedia, than?: LibraryMedia) =>
+  !than || new Date(item.lastUpdatedAt).getTime() > new Date(than.lastUpdatedAt).getTime();
+
+/** Write the native items RxDB doesn't have, or has an older copy of */
+const copyToRxDB = async (db: WatchfolioDatabase, items: LibraryMedia[]) => {
+  if (items.length === 0) return;
+  const docs = await db.libraryMedia.findByIds(items.map((item) => item.id)).exec();
+  const newer = items.filter((item) => isNewer(item, docs.get(item.id)?.toJSON() as LibraryMedia | undefined));
+  if (newer.length > 0) await db.libraryMedia.bulkUpsert(newer);
+};
+
+/**
+ * Keep RxDB and the native store (library.rs) that the tray, shortcuts and deep links use in step.
+ * RxDB changes are copied over as they happen, and changes made natively come back through
+ * `library:changed`. The native store outlives a wiped webview cache, so an empty RxDB starts from it.
+ */
+export function useNativeLibrary() {
+  useEffect(() => {
+    if (!isDesktop() || getWindowLabel() !== 'main') return;
+
+    let current: WatchfolioDatabase | null = null;
+    let subscription: Subscription | null = null;
+    let unlistenNative: (() => void) | null = null;
+    let disposed = false;
+    // Applied one at a time, so a change can't be overtaken by the full copy taken before it
+    let queue = Promise.resolve();
+    const enqueue = (task: () => Promise<unknown>) => {
+      queue = queue.then(task).catch((error) => log('ERR', 'Failed to sync library with desktop:', error));
+    };
+
+    const unsubscribeDB = subscribeToDB((db) => {
+      subscription?.unsubscribe();
+      subscription = null;
+      current = db;
+      // A destroyed database leaves the native store alone: it is what the next one starts from
+      if (!db) return;
+
+      subscription = db.libraryMedia.$.subscribe((event) => {
+        if (event.operation === 'DELETE') {
+          enqueue(() => invoke('library_delete', { ids: [event.documentId] }));
+        } else {
+          enqueue(() => invoke('library_upsert', { items: [event.documentData] }));
+        }
+      });
+
+      enqueue(async () => {
+        const docs = await db.libraryMedia.find().exec();
+        const items = docs.map((doc) => doc.toJSON() as LibraryMedia);
+        const native = await invoke<LibraryMedia[]>('library_list');
+
+        if (items.length === 0 && native.length > 0) {
+          log(`Restoring ${native.length} library items from the desktop store`);
+        }
+        const nativeById = new Map(native.map((item) => [item.id, item]));
+        const changed = items.filter((item) => isNewer(item, nativeById.get(item.id)));
+        if (changed.length > 0) await invoke('library_upsert', { items: changed });
+
+        // An empty RxDB is filled. Otherwise only newer copies are taken, picking up tray changes made
+        // while nobody listened, and titles RxDB lacks stay out so deletions made elsewhere stick.
+        const ids = new Set(items.map((item) => item.id));
+        await copyToRxDB(db, ids.size === 0 ? native : native.filter((item) => ids.has(item.id)));
+      });
+    });
+
+    // What copying from RxDB writes is never newer than RxDB, so only native changes get through
+    listen<LibraryChange>('library:changed', (event) => {
+      if (event.payload.kind !== 'upserted') return;
+      enqueue(async () => {
+        const db = current;
+        if (!db) return;
+        const items = await Promise.all(
+          event.payload.ids.map((id) => invoke<LibraryMedia | null>('library_get', { id }))
+        );
+        await copyToRxDB(db, items.filter((item): item is LibraryMedia => item !== null));
+      });
+    }).then((fn) => {
+      if (disposed) fn();
+      else unlistenNative = fn;
+    });
+
+    return () => {
+      disposed = true;
+      unsubscribeDB();
+      subscription?.unsubscribe();
+      unlistenNative?.();
+    };
+  }, []);
+}
@@ -1,8 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { relaunch } from '@tauri-apps/plugin-process';
-import { isDesktop } from '@/lib/platform';
+import { errorMessage, isDesktop, type DesktopError } from '@/lib/platform';
 import { addToast } from '@heroui/react';
 
 interface UpdateInfo {
@@ -69,17 +68,35 @@ export function useUpdater() {
         }
       );
 
-      const unlistenReady = await listen('update-ready-to-install', () => {
-        log('Update ready to install');
+      const unlistenReady = await listen<UpdateInfo>('update-ready-to-install', (event) => {
+        log('Update ready to install:', event.payload);
+        setUpdateAvailable(true);
+        setUpdateInfo(event.payload);
         setDownloading(false);
         setReadyToInstall(true);
         addToast({
-          title: 'Update downloaded successfully',
-          description: 'The app will restart to complete installation',
+          title: `Update v${event.payload.version} downloaded`,
+          description: 'It will be installed when you quit, or restart now',
           color: 'success',
         });
       });
 
+      const unlistenFailed = await listen<DesktopError>('update-download-failed', (event) => {
+        log('Update download failed:', event.payload);
+        setDownloading(false);
+        addToast({
+          title: 'Failed to download update',
+          description: event.payload.message,
+          color: 'danger',
+        });
+      });
+
+      const unlistenCanceled = await listen('update-download-canceled', () => {
+        log('Update download canceled');
+        setDownloading(false);
+        setDownloadProgress(0);
+      });
+
       const unlistenInstalled = await listen('update-installed', () => {
         log('Update installed');
         addToast({
@@ -94,6 +111,8 @@ export function useUpdater() {
         unlistenNotAvailable();
         unlistenProgress();
         unlistenReady();
+        unlistenFailed();
+        unlistenCanceled();
         unlistenInstalled();
       };
     };
@@ -123,7 +142,7 @@ export function useUpdater() {
         if (showToast) {
           addToast({
             title: 'Failed to check for updates',
-            description: error instanceof Error ? error.message : 'Unknown error',
+            description: errorMessage(error),
             color: 'danger',
           });
         }
@@ -145,17 +164,34 @@ export function useUpdater() {
     setDownloadProgress(0);
 
     try {
-      await invoke('install_update');
+      await invoke('download_update');
+    } catch (error) {
+      console.error('Failed to download update:', error);
+      setDownloading(false);
+      addToast({
+        title: 'Failed to download update',
+        description: errorMessage(error),
+        color: 'danger',
+      });
+    }
+  }, []);
 
-      await new Promise((resolve) => setTimeout(resolve, 1000));
+  const cancelDownload = useCallback(async () => {
+    try {
+      await invoke('cancel_update_download');
+    } catch (error) {
+      console.error('Failed to cancel update download:', error);
+    }
+  }, []);
 
-      await relaunch();
+  const restartToUpdate = useCallback(async () => {
+    try {
+      await invoke('restart_to_update');
     } catch (error) {
-      console.error('Failed to download/install update:', error);
-      setDownloading(false);
+      console.error('Failed to install update:', error);
       addToast({
         title: 'Failed to install update',
-        description: error instanceof Error ? error.message : 'Unknown error',
+        description: errorMessage(error),
         color: 'danger',
       });
     }
@@ -167,6 +203,26 @@ export function useUpdater() {
     setReadyToInstall(false);
   }, []);
 
+  const remindLater = useCallback(async () => {
+    dismissUpdate();
+    try {
+      await invoke('remind_update_later', { hours: 24 });
+    } catch (error) {
+      console.error('Failed to postpone update:', error);
+    }
+  }, [dismissUpdate]);
+
+  const skipVersion = useCallback(async () => {
+    if (!updateInfo) return;
+    const { version } = updateInfo;
+    dismissUpdate();
+    try {
+      await invoke('skip_update_version', { version });
+    } catch (error) {
+      console.error('Failed to skip update:', error);
+    }
+  }, [updateInfo, dismissUpdate]);
+
   return {
     checking,
     updateAvailable,
@@ -176,6 +232,10 @@ export function useUpdater() {
     readyToInstall,
     checkForUpdates,
     downloadAndInstall,
+    cancelDownload,
+    restartToUpdate,
     dismissUpdate,
+    remindLater,
+    skipVersion,
   };
 }
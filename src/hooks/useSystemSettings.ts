@@ -1,7 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
-import { enable, disable, isEnabled } from '@tauri-apps/plugin-autostart';
 import { invoke } from '@tauri-apps/api/core';
-import { getCurrentWindow } from '@tauri-apps/api/window';
 import { addToast } from '@heroui/react';
 import { isDesktop } from '@/lib/platform';
 
@@ -50,24 +48,17 @@ export function useSystemSettings() {
     const initialize = async () => {
       try {
         // Load autostart status
-        const enabled = await isEnabled();
+        const enabled = await invoke<boolean>('is_autostart_enabled');
         setSettings((prev) => ({ ...prev, launchOnStartup: enabled }));
 
-        // Sync keepRunningInBackground with Rust
+        // Sync with Rust, which decides window visibility on the next autostart
         const stored = getSettingsFromStorage();
-        await invoke('set_keep_running_in_background', {
-          enabled: stored.keepRunningInBackground,
+        await invoke('update_system_settings', {
+          patch: {
+            startMinimized: stored.startMinimized,
+            keepRunningInBackground: stored.keepRunningInBackground,
+          },
         });
-
-        // Check if app was autostarted and handle window visibility
-        const isAutostarted = await invoke<boolean>('was_autostarted');
-
-        if (isAutostarted && !stored.startMinimized) {
-          // User wants window visible on startup
-          const window = getCurrentWindow();
-          await window.show();
-          await window.setFocus();
-        }
       } catch (error) {
         console.error('Failed to initialize system settings:', error);
       }
@@ -81,20 +72,16 @@ export function useSystemSettings() {
 
     setIsLoading(true);
     try {
-      // Use plugin's enable/disable functions
-      if (enabledValue) {
-        await enable();
-      } else {
-        await disable();
-      }
+      // Through Rust, so its launch_on_startup setting and the diagnostics follow
+      const enabled = await invoke<boolean>(enabledValue ? 'enable_autostart' : 'disable_autostart');
 
-      const newSettings = { ...settings, launchOnStartup: enabledValue };
+      const newSettings = { ...settings, launchOnStartup: enabled };
       setSettings(newSettings);
       saveSettingsToStorage(newSettings);
 
       addToast({
         title: 'Settings updated',
-        description: `Launch on startup ${enabledValue ? 'enabled' : 'disabled'}`,
+        description: `Launch on startup ${enabled ? 'enabled' : 'disabled'}`,
         color: 'success',
       });
     } catch (error) {
@@ -115,6 +102,11 @@ export function useSystemSettings() {
       setSettings(newSettings);
       saveSettingsToStorage(newSettings);
 
+      // Sync with Rust
+      invoke('update_system_settings', { patch: { startMinimized: enabled } }).catch((error) =>
+        console.error('Failed to sync start minimized setting with Rust:', error)
+      );
+
       addToast({
         title: 'Settings updated',
         description: `Start minimized ${enabled ? 'enabled' : 'disabled'}`,
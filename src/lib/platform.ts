@@ -42,3 +42,25 @@ export function getWindowLabel() {
   
   return getCurrentWindow().label;
 }
+
+/**
+ * Error returned by desktop commands; branch on `code`, show `message`
+ */
+export interface DesktopError {
+  code: string;
+  message: string;
+  details?: unknown;
+}
+
+export function isDesktopError(error: unknown): error is DesktopError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/**
+ * Readable message for anything a command or event can reject with
+ */
+export function errorMessage(error: unknown, fallback = 'Unknown error'): string {
+  if (isDesktopError(error) || error instanceof Error) return error.message;
+  if (typeof error === 'string') return error;
+  return fallback;
+}
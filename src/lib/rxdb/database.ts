@@ -14,7 +14,7 @@ addRxPlugin(RxDBQueryBuilderPlugin);
 addRxPlugin(RxDBUpdatePlugin);
 addRxPlugin(RxDBMigrationSchemaPlugin);
 
-interface WatchfolioDatabase {
+export interface WatchfolioDatabase {
   libraryMedia: RxCollection<LibraryMedia>;
   remove: () => Promise<void>;
 }
@@ -22,6 +22,20 @@ interface WatchfolioDatabase {
 let dbInstance: WatchfolioDatabase | null = null;
 let dbPromise: Promise<WatchfolioDatabase> | null = null;
 
+type DBListener = (db: WatchfolioDatabase | null) => void;
+const dbListeners = new Set<DBListener>();
+
+/**
+ * Get called with the database whenever one is created, and with null once it is destroyed
+ */
+export const subscribeToDB = (listener: DBListener): (() => void) => {
+  dbListeners.add(listener);
+  if (dbInstance) listener(dbInstance);
+  return () => {
+    dbListeners.delete(listener);
+  };
+};
+
 type DBStatus = 'not-initialized' | 'initializing' | 'ready';
 
 export const getDBStatus = (): DBStatus => {
@@ -79,6 +93,7 @@ export const getWatchfolioDB = async (): Promise<WatchfolioDatabase> => {
       });
 
     dbInstance = db as unknown as WatchfolioDatabase;
+    dbListeners.forEach((listener) => listener(dbInstance));
     return dbInstance;
   });
 
@@ -90,6 +105,7 @@ export const destroyDB = async (): Promise<void> => {
   if (dbInstance) {
     await dbInstance.remove();
     dbInstance = null;
+    dbListeners.forEach((listener) => listener(null));
   }
   dbPromise = null;
   log('Watchfolio database destroyed');
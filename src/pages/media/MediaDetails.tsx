@@ -10,6 +10,7 @@ import { useQuery } from '@tanstack/react-query';
 import { getDetails } from '@/lib/api/TMDB';
 import { Status } from '@/components/ui/Status';
 import { usePageTitle } from '@/hooks/usePageTitle';
+import { useCurrentMedia } from '@/hooks/desktop/useCurrentMedia';
 import { queryKeys } from '@/lib/react-query';
 
 export default function MediaDetails({ type }: { type: MediaType }) {
@@ -24,6 +25,7 @@ export default function MediaDetails({ type }: { type: MediaType }) {
   } = useQuery({ queryKey: queryKeys.details(type, id), queryFn: async () => await getDetails(type, id) });
 
   usePageTitle(isLoading ? 'Loading...' : (media as Movie)?.title || (media as TvShow)?.name || '');
+  useCurrentMedia(type, media?.id, (media as Movie)?.title || (media as TvShow)?.name);
 
   if (isLoading) return <DetailsSkeleton type={type} />;
   if (!media)